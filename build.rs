@@ -0,0 +1,129 @@
+//! Generates the keymap from a QMK Configurator `keymap.json`, if the
+//! `KEYMAP_JSON` environment variable is set to its path.
+//!
+//! Keycodes are parsed with `engine`'s `Keycode::from_str`, so a typo or a
+//! keycode used where it can't be (like `LT(1, MO(2))`) is reported here
+//! rather than as a confusing error in generated code.
+
+use std::{env, fmt::Write, fs, path::Path, process};
+
+use engine::keycode::{Keycode, Mods};
+use serde_json::Value;
+
+/// The layouts we have macros for, with the number of keys they take.
+const LAYOUTS: &[(&str, &str, usize)] = &[
+    ("LAYOUT_planck_grid", "layout_planck_grid", 48),
+    ("LAYOUT_ortho_4x12", "layout_planck_grid", 48),
+    ("LAYOUT_planck_mit", "layout_planck_mit", 47),
+    ("LAYOUT_planck_2x2u", "layout_planck_2x2u", 46),
+];
+
+fn main() {
+    println!("cargo:rustc-check-cfg=cfg(keymap_json)");
+    println!("cargo:rerun-if-env-changed=KEYMAP_JSON");
+    let Some(path) = env::var_os("KEYMAP_JSON") else {
+        return;
+    };
+    println!("cargo:rerun-if-changed={}", Path::new(&path).display());
+
+    let result = fs::read_to_string(&path)
+        .map_err(|e| e.to_string())
+        .and_then(|json| generate(&json));
+    match result {
+        Ok(code) => {
+            let out = Path::new(&env::var_os("OUT_DIR").unwrap()).join("keymap.rs");
+            fs::write(out, code).unwrap();
+            println!("cargo:rustc-cfg=keymap_json");
+        }
+        Err(e) => {
+            eprintln!("error: {}: {e}", Path::new(&path).display());
+            process::exit(1);
+        }
+    }
+}
+
+/// A keycode as a Rust expression, in the scope of `engine::keycode::qmk`.
+///
+/// That is the keycode's name, except where the name isn't valid Rust.
+fn expression(keycode: Keycode) -> String {
+    match keycode {
+        Keycode::ModTap(mod_tap) if keycode.to_string().starts_with("MT(") => format!(
+            "MT({}, {})",
+            mods_expression(mod_tap.mods()),
+            Keycode::Hid(mod_tap.keycode())
+        ),
+        Keycode::User(index) => format!("Keycode::User({index})"),
+        keycode => keycode.to_string(),
+    }
+}
+
+/// Modifiers as a Rust expression. Their name may be a list like
+/// `MOD_LCTL | MOD_LSFT`, or `0`.
+fn mods_expression(mods: Mods) -> String {
+    if mods.is_empty() {
+        return "engine::keycode::Mods::NONE".to_string();
+    }
+    let name = mods.to_string();
+    let mut names = name.split(" | ");
+    let first = names.next().unwrap().to_string();
+    names.fold(first, |expression, name| {
+        format!("{expression}.union({name})")
+    })
+}
+
+fn generate(json: &str) -> Result<String, String> {
+    let json: Value = serde_json::from_str(json).map_err(|e| e.to_string())?;
+    let layout = json["layout"]
+        .as_str()
+        .ok_or("missing `layout` (e.g. \"LAYOUT_planck_grid\")")?;
+    let &(_, layout_macro, key_count) = LAYOUTS
+        .iter()
+        .find(|(name, _, _)| *name == layout)
+        .ok_or_else(|| {
+            let supported: Vec<_> = LAYOUTS.iter().map(|(name, _, _)| *name).collect();
+            format!(
+                "unsupported layout `{layout}`, expected one of: {}",
+                supported.join(", ")
+            )
+        })?;
+    let layers = json["layers"]
+        .as_array()
+        .ok_or("missing `layers` (a list of lists of keycodes)")?;
+    if layers.is_empty() {
+        return Err("no layers".into());
+    }
+
+    let mut code = String::new();
+    writeln!(code, "// Generated by build.rs from keymap.json.").unwrap();
+    writeln!(code, "engine::keymap! {{").unwrap();
+    writeln!(
+        code,
+        "    pub static LAYERS: [[Keycode; scan::COLS]; scan::ROWS] = {{"
+    )
+    .unwrap();
+    for (i, layer) in layers.iter().enumerate() {
+        let keys = layer
+            .as_array()
+            .ok_or_else(|| format!("layer {i} is not a list of keycodes"))?;
+        if keys.len() != key_count {
+            return Err(format!(
+                "layer {i} has {} keys, but {layout} has {key_count}",
+                keys.len()
+            ));
+        }
+        writeln!(code, "        LAYER_{i} => engine::{layout_macro}![").unwrap();
+        for (j, key) in keys.iter().enumerate() {
+            let key = key
+                .as_str()
+                .ok_or_else(|| format!("layer {i}, key {j}: expected a string, got {key}"))?;
+            let keycode: Keycode = key
+                .parse()
+                .map_err(|e| format!("layer {i}, key {j}: `{key}`: {e}"))?;
+            writeln!(code, "            {},", expression(keycode)).unwrap();
+        }
+        writeln!(code, "        ],").unwrap();
+    }
+    writeln!(code, "    }};").unwrap();
+    writeln!(code, "}}").unwrap();
+    Ok(code)
+}
@@ -0,0 +1,114 @@
+//! Backlight brightness levels and breathing.
+//!
+//! This only decides how bright the backlight should be; driving the LEDs
+//! (e.g. with a timer PWM output) is up to the firmware.
+
+use crate::keycode::SystemKeycode;
+
+/// The state of the backlight, changed by the `BL_*` keys.
+///
+/// The brightness is one of `levels` steps, where level 0 is off. Times are
+/// given in milliseconds from a wrapping `u16` clock.
+pub struct Backlight {
+    levels: u8,
+    level: u8,
+    enabled: bool,
+    breathing: bool,
+    // Duration of one full breath (dim, bright, dim again).
+    breathing_period: u16,
+}
+
+impl Backlight {
+    /// A backlight that starts out off, and is turned on at full brightness.
+    pub const fn new(levels: u8, breathing_period: u16) -> Self {
+        assert!(levels > 0);
+        assert!(breathing_period > 1);
+        Self {
+            levels,
+            level: levels,
+            enabled: false,
+            breathing: false,
+            breathing_period,
+        }
+    }
+
+    /// The current brightness level, or 0 if the backlight is off.
+    pub fn level(&self) -> u8 {
+        if self.enabled {
+            self.level
+        } else {
+            0
+        }
+    }
+
+    pub fn is_breathing(&self) -> bool {
+        self.breathing
+    }
+
+    /// Applies a backlight key press. Other keys are ignored.
+    pub fn handle(&mut self, keycode: SystemKeycode) {
+        match keycode {
+            SystemKeycode::BacklightDown => self.decrease(),
+            SystemKeycode::BacklightUp => self.increase(),
+            SystemKeycode::BacklightStep => self.step(),
+            SystemKeycode::BacklightToggle => self.toggle(),
+            SystemKeycode::BacklightBreathing => self.breathing = !self.breathing,
+            _ => {}
+        }
+    }
+
+    /// One level brighter, turning the backlight on if it was off.
+    pub fn increase(&mut self) {
+        if !self.enabled {
+            self.level = 0;
+        }
+        self.set_level(self.level.saturating_add(1).min(self.levels));
+    }
+
+    /// One level dimmer, down to off.
+    pub fn decrease(&mut self) {
+        self.set_level(self.level().saturating_sub(1));
+    }
+
+    /// One level brighter, going back to off after the brightest level.
+    pub fn step(&mut self) {
+        match self.level() {
+            level if level < self.levels => self.set_level(level + 1),
+            _ => self.set_level(0),
+        }
+    }
+
+    /// Turns the backlight on or off, keeping its level.
+    pub fn toggle(&mut self) {
+        self.enabled = !self.enabled;
+    }
+
+    fn set_level(&mut self, level: u8) {
+        if level == 0 {
+            // Keep the last level, so toggling turns the backlight back on
+            // at full brightness rather than at 0.
+            self.level = self.levels;
+            self.enabled = false;
+        } else {
+            self.level = level;
+            self.enabled = true;
+        }
+    }
+
+    /// The PWM duty cycle to drive the backlight with, where 255 is fully on.
+    pub fn duty(&self, now: u16) -> u8 {
+        let duty = u32::from(self.level()) * 255 / u32::from(self.levels);
+        if !self.breathing {
+            return duty as u8;
+        }
+        // Triangle wave, from dark through the set brightness and back.
+        let half = u32::from(self.breathing_period / 2);
+        let t = u32::from(now % self.breathing_period);
+        let t = if t < half {
+            t
+        } else {
+            u32::from(self.breathing_period) - t
+        };
+        (duty * t.min(half) / half) as u8
+    }
+}
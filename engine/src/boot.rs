@@ -0,0 +1,41 @@
+//! The boot protocol keyboard report, for hosts that don't parse report
+//! descriptors (BIOS/UEFI setup, some KVMs).
+
+use crate::{keycode::HidKeycode, nkro::NkroKeyboardReport};
+
+/// Reported in every key slot when more keys are pressed than fit.
+const ERROR_ROLL_OVER: u8 = 0x01;
+const FIRST_MODIFIER: u8 = HidKeycode::LeftControl as u8;
+
+/// An 8-byte boot keyboard report: modifiers, a reserved byte, and up to 6
+/// pressed keys (6KRO).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BootKeyboardReport {
+    bytes: [u8; 8],
+}
+
+impl BootKeyboardReport {
+    /// Converts an NKRO report, reporting a roll-over error if more than 6
+    /// non-modifier keys are pressed.
+    pub fn from_nkro(report: &NkroKeyboardReport) -> Self {
+        let mut bytes = [0; 8];
+        for i in 0..8 {
+            if report.is_pressed(FIRST_MODIFIER + i) {
+                bytes[0] |= 1 << i;
+            }
+        }
+        // Usages below 4 are error codes, not keys.
+        let mut keys = (4..FIRST_MODIFIER).filter(|&key| report.is_pressed(key));
+        for (slot, key) in bytes[2..].iter_mut().zip(&mut keys) {
+            *slot = key;
+        }
+        if keys.next().is_some() {
+            bytes[2..].fill(ERROR_ROLL_OVER);
+        }
+        Self { bytes }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
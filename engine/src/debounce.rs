@@ -0,0 +1,117 @@
+//! Filtering of switch chatter from raw matrix scans.
+//!
+//! The algorithms follow the ones offered by QMK. "Eager" algorithms report a
+//! change as soon as it is seen and then ignore the key (or row) for the
+//! debounce window; "deferred" algorithms only report a change once the key
+//! has been stable for the window.
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Algorithm {
+    /// Report changes immediately, then ignore the key for the window.
+    /// Lowest latency, but susceptible to noise.
+    EagerPerKey,
+    /// Report a change once the key has been stable for the window.
+    /// Resistant to noise, but adds the window as latency.
+    DeferPerKey,
+    /// Report changes immediately, then ignore the whole row for the window.
+    /// Uses less bookkeeping than per-key debouncing.
+    EagerPerRow,
+}
+
+/// Debounces raw matrix scans, where each row is a bitmask of pressed
+/// columns.
+///
+/// Times are given in milliseconds from a wrapping `u16` clock.
+pub struct Debouncer<const ROWS: usize, const COLS: usize> {
+    algorithm: Algorithm,
+    window: u16,
+    debounced: [u16; ROWS],
+    // Keys (or rows, for `EagerPerRow`) whose timer is running.
+    active: [u16; ROWS],
+    // When the timer of each key (or row, in column 0) was started.
+    since: [[u16; COLS]; ROWS],
+}
+
+impl<const ROWS: usize, const COLS: usize> Debouncer<ROWS, COLS> {
+    pub const fn new(algorithm: Algorithm, window: u16) -> Self {
+        assert!(COLS <= 16);
+        Self {
+            algorithm,
+            window,
+            debounced: [0; ROWS],
+            active: [0; ROWS],
+            since: [[0; COLS]; ROWS],
+        }
+    }
+
+    /// The debounced state as of the last update.
+    pub fn state(&self) -> &[u16; ROWS] {
+        &self.debounced
+    }
+
+    /// Feeds a raw scan into the debouncer, returning the debounced state.
+    pub fn update(&mut self, raw: &[u16; ROWS], now: u16) -> &[u16; ROWS] {
+        for (i, &raw) in raw.iter().enumerate() {
+            match self.algorithm {
+                Algorithm::EagerPerKey => self.update_eager_pk(i, raw, now),
+                Algorithm::DeferPerKey => self.update_defer_pk(i, raw, now),
+                Algorithm::EagerPerRow => self.update_eager_pr(i, raw, now),
+            }
+        }
+        &self.debounced
+    }
+
+    fn expired(&self, i: usize, j: usize, now: u16) -> bool {
+        now.wrapping_sub(self.since[i][j]) >= self.window
+    }
+
+    fn update_eager_pk(&mut self, i: usize, raw: u16, now: u16) {
+        for j in 0..COLS {
+            let bit = 1 << j;
+            if (self.active[i] & bit) != 0 {
+                if !self.expired(i, j, now) {
+                    continue;
+                }
+                self.active[i] &= !bit;
+            }
+            if ((raw ^ self.debounced[i]) & bit) != 0 {
+                self.debounced[i] ^= bit;
+                self.active[i] |= bit;
+                self.since[i][j] = now;
+            }
+        }
+    }
+
+    fn update_defer_pk(&mut self, i: usize, raw: u16, now: u16) {
+        for j in 0..COLS {
+            let bit = 1 << j;
+            if ((raw ^ self.debounced[i]) & bit) == 0 {
+                // Bounced back (or never changed); restart the timer next time.
+                self.active[i] &= !bit;
+            } else {
+                if (self.active[i] & bit) == 0 {
+                    self.active[i] |= bit;
+                    self.since[i][j] = now;
+                }
+                if self.expired(i, j, now) {
+                    self.debounced[i] ^= bit;
+                    self.active[i] &= !bit;
+                }
+            }
+        }
+    }
+
+    fn update_eager_pr(&mut self, i: usize, raw: u16, now: u16) {
+        if self.active[i] != 0 {
+            if !self.expired(i, 0, now) {
+                return;
+            }
+            self.active[i] = 0;
+        }
+        if raw != self.debounced[i] {
+            self.debounced[i] = raw;
+            self.active[i] = 1;
+            self.since[i][0] = now;
+        }
+    }
+}
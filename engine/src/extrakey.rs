@@ -0,0 +1,123 @@
+//! System control and consumer control (media key) reports.
+//!
+//! Both kinds share one HID interface and are told apart by their report ID,
+//! so more of them can be added without using up endpoints.
+
+pub const REPORT_ID_SYSTEM: u8 = 1;
+pub const REPORT_ID_CONSUMER: u8 = 2;
+
+/// The report descriptor of the extra key interface.
+#[rustfmt::skip]
+pub const DESCRIPTOR: &[u8] = &[
+    // System control
+    0x05, 0x01,                 // Usage Page (Generic Desktop)
+    0x09, 0x80,                 // Usage (System Control)
+    0xa1, 0x01,                 // Collection (Application)
+    0x85, REPORT_ID_SYSTEM,     //   Report ID
+    0x19, 0x01,                 //   Usage Minimum (0x01)
+    0x2a, 0xb7, 0x00,           //   Usage Maximum (0xb7)
+    0x15, 0x01,                 //   Logical Minimum (0x01)
+    0x26, 0xb7, 0x00,           //   Logical Maximum (0xb7)
+    0x95, 0x01,                 //   Report Count (1)
+    0x75, 0x10,                 //   Report Size (16)
+    0x81, 0x00,                 //   Input (Data, Array, Absolute)
+    0xc0,                       // End Collection
+    // Consumer control
+    0x05, 0x0c,                 // Usage Page (Consumer)
+    0x09, 0x01,                 // Usage (Consumer Control)
+    0xa1, 0x01,                 // Collection (Application)
+    0x85, REPORT_ID_CONSUMER,   //   Report ID
+    0x19, 0x01,                 //   Usage Minimum (0x001)
+    0x2a, 0xa0, 0x02,           //   Usage Maximum (0x2a0)
+    0x15, 0x01,                 //   Logical Minimum (0x001)
+    0x26, 0xa0, 0x02,           //   Logical Maximum (0x2a0)
+    0x95, 0x01,                 //   Report Count (1)
+    0x75, 0x10,                 //   Report Size (16)
+    0x81, 0x00,                 //   Input (Data, Array, Absolute)
+    0xc0,                       // End Collection
+];
+
+/// How many held keys a report keeps track of. Pressing another one forgets
+/// the one pressed first.
+const HELD_USAGES: usize = 4;
+
+/// A report with one usage pressed at a time: the most recently pressed key
+/// that is still held. A usage of 0 (out of the logical range) means nothing
+/// is pressed.
+#[derive(Clone, Copy, Debug)]
+pub struct ExtraKeyReport {
+    report_id: u8,
+    // The held usages, oldest first.
+    held: [u16; HELD_USAGES],
+    len: u8,
+}
+
+impl ExtraKeyReport {
+    /// An empty system control report.
+    pub const fn system() -> Self {
+        Self::new(REPORT_ID_SYSTEM)
+    }
+
+    /// An empty consumer control report.
+    pub const fn consumer() -> Self {
+        Self::new(REPORT_ID_CONSUMER)
+    }
+
+    const fn new(report_id: u8) -> Self {
+        Self {
+            report_id,
+            held: [0; HELD_USAGES],
+            len: 0,
+        }
+    }
+
+    /// Presses a key, which is reported until it is released, or until
+    /// another key is pressed.
+    pub fn press(&mut self, usage: u16) {
+        self.release(usage);
+        if self.len as usize == HELD_USAGES {
+            self.held.copy_within(1.., 0);
+            self.len -= 1;
+        }
+        self.held[self.len as usize] = usage;
+        self.len += 1;
+    }
+
+    /// Releases a key. If it is the one reported, the key pressed before it
+    /// is reported again, if it is still held.
+    pub fn release(&mut self, usage: u16) {
+        let len = self.len as usize;
+        if let Some(i) = self.held[..len].iter().position(|&held| held == usage) {
+            self.held.copy_within(i + 1..len, i);
+            self.len -= 1;
+        }
+    }
+
+    pub fn report_id(&self) -> u8 {
+        self.report_id
+    }
+
+    /// The usage of the pressed key, or 0 if none is pressed.
+    pub fn usage(&self) -> u16 {
+        match self.len {
+            0 => 0,
+            len => self.held[len as usize - 1],
+        }
+    }
+
+    /// The report as sent to the host, starting with the report ID.
+    pub fn to_bytes(&self) -> [u8; 3] {
+        let [lo, hi] = self.usage().to_le_bytes();
+        [self.report_id, lo, hi]
+    }
+}
+
+/// Reports are equal if they are sent as the same bytes, whatever else is
+/// held.
+impl PartialEq for ExtraKeyReport {
+    fn eq(&self, other: &Self) -> bool {
+        self.to_bytes() == other.to_bytes()
+    }
+}
+
+impl Eq for ExtraKeyReport {}
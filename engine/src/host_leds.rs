@@ -0,0 +1,49 @@
+//! The lock state reported by the host (Caps Lock etc.).
+
+/// The LEDs the host wants lit, from the keyboard's output report.
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+#[repr(transparent)]
+pub struct HostLeds(u8);
+
+impl HostLeds {
+    pub const NUM_LOCK: u8 = 0x01;
+    pub const CAPS_LOCK: u8 = 0x02;
+    pub const SCROLL_LOCK: u8 = 0x04;
+    pub const COMPOSE: u8 = 0x08;
+    pub const KANA: u8 = 0x10;
+
+    pub const fn from_bits(bits: u8) -> Self {
+        Self(bits)
+    }
+
+    /// Parses the data of an output report (SET_REPORT or the OUT endpoint).
+    ///
+    /// Returns `None` if the report is empty.
+    pub fn from_report(data: &[u8]) -> Option<Self> {
+        data.first().map(|&bits| Self(bits))
+    }
+
+    pub const fn bits(&self) -> u8 {
+        self.0
+    }
+
+    pub const fn num_lock(&self) -> bool {
+        (self.0 & Self::NUM_LOCK) != 0
+    }
+
+    pub const fn caps_lock(&self) -> bool {
+        (self.0 & Self::CAPS_LOCK) != 0
+    }
+
+    pub const fn scroll_lock(&self) -> bool {
+        (self.0 & Self::SCROLL_LOCK) != 0
+    }
+
+    pub const fn compose(&self) -> bool {
+        (self.0 & Self::COMPOSE) != 0
+    }
+
+    pub const fn kana(&self) -> bool {
+        (self.0 & Self::KANA) != 0
+    }
+}
@@ -0,0 +1,811 @@
+use crate::{
+    extrakey::ExtraKeyReport,
+    host_leds::HostLeds,
+    keycode::{
+        qmk::{KC_NO, KC_TRNS},
+        HidKeycode, Keycode, LayerAction, SystemKeycode,
+    },
+    layer::{LayerState, MAX_LAYERS},
+    matrix::KeyEvent,
+    mousekey::{Acceleration, MouseKeys, MouseReport},
+    nkro::NkroKeyboardReport,
+    queue::Queue,
+    tap_dance::{self, Dance, TapDance},
+    tap_hold::{Decision, Pending},
+};
+
+/// How many key events can be deferred while a tap-hold decision is pending.
+/// If more events come in, the key is decided to be held.
+const DEFERRED_EVENTS: usize = 8;
+
+/// How many tap dance keys can be held down with their action pressed. The
+/// action of any more is tapped instead.
+const HELD_DANCES: usize = 4;
+
+/// How many reports can be queued before they are coalesced.
+const QUEUED_REPORTS: usize = 8;
+/// How many system keys can be queued before further presses are dropped.
+const QUEUED_SYSTEM_KEYS: usize = 4;
+
+/// A keymap with up to 32 layers, indexed by `[layer][row][col]`.
+pub type Keymap<const ROWS: usize, const COLS: usize> = [[[Keycode; COLS]; ROWS]];
+
+/// Activates a layer whenever all of a set of layers are active.
+#[derive(Clone, Copy)]
+pub struct LayerCondition {
+    /// The layers that need to be active.
+    pub layers: LayerState,
+    /// The layer to activate.
+    pub then: u8,
+}
+
+impl LayerCondition {
+    /// Activates layer `c` while both layers `a` and `b` are active, like the
+    /// Lower + Raise = Adjust layer of the stock Planck layout.
+    pub const fn tri_layer(a: u8, b: u8, c: u8) -> Self {
+        Self {
+            layers: LayerState::single(a).with(b),
+            then: c,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Config {
+    /// How long a tapped one-shot layer waits for the next key before it is
+    /// cancelled, in milliseconds. `None` waits forever.
+    pub oneshot_timeout: Option<u16>,
+    /// How long a tap-hold key has to be held to act as held, in
+    /// milliseconds.
+    pub tapping_term: u16,
+    /// Hold a tap-hold key if another key is pressed and released while it is
+    /// held, even within the tapping term.
+    pub permissive_hold: bool,
+    /// Hold a tap-hold key as soon as another key is pressed while it is held.
+    pub hold_on_other_key_press: bool,
+    /// Tap a tap-hold key that was held past the tapping term, if no other key
+    /// was pressed before it was released.
+    pub retro_tapping: bool,
+    /// Layers that are activated by combinations of other layers. They are
+    /// evaluated in order, so a condition can depend on the layers activated
+    /// by the ones before it.
+    pub layer_conditions: &'static [LayerCondition],
+    /// How long a tap dance waits for the next tap, or for the key to be
+    /// released before it is held, in milliseconds.
+    pub tap_dance_term: u16,
+    /// The tap dance keys, indexed by `Keycode::TapDance`.
+    pub tap_dances: &'static [TapDance],
+    /// How fast mouse keys move the pointer.
+    pub mouse_cursor: Acceleration,
+    /// How fast mouse keys scroll.
+    pub mouse_wheel: Acceleration,
+}
+
+impl Config {
+    pub const fn new() -> Self {
+        Self {
+            oneshot_timeout: Some(3000),
+            tapping_term: 200,
+            permissive_hold: false,
+            hold_on_other_key_press: false,
+            retro_tapping: false,
+            layer_conditions: &[],
+            tap_dance_term: 200,
+            tap_dances: &[],
+            mouse_cursor: Acceleration::CURSOR,
+            mouse_wheel: Acceleration::WHEEL,
+        }
+    }
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Oneshot {
+    Idle,
+    /// The one-shot key is held down. If another key is pressed before it is
+    /// released, it behaves like a momentary layer key.
+    Held {
+        layer: u8,
+        interrupted: bool,
+    },
+    /// The one-shot key was tapped; the layer stays active for the next key.
+    Armed {
+        layer: u8,
+        since: u16,
+    },
+}
+
+/// Resolves key events through the keymap and layers into a keyboard report.
+///
+/// Times are given in milliseconds from a wrapping `u16` clock.
+pub struct Keyboard<const ROWS: usize, const COLS: usize> {
+    keymap: &'static Keymap<ROWS, COLS>,
+    config: Config,
+    // Layers stacked on top of the default layer by MO/TG/OSL/TO.
+    layer_state: LayerState,
+    // The base layer(s), set by DF. Always active underneath `layer_state`.
+    default_layer_state: LayerState,
+    oneshot: Oneshot,
+    // The layer each pressed key was resolved on, so that releasing it
+    // releases the same keycode even if the active layers changed since.
+    source_layers: [[Option<u8>; COLS]; ROWS],
+    // The tap-hold key waiting for a decision, if any, and the events that
+    // came in after it.
+    pending: Option<Pending>,
+    deferred: Queue<(KeyEvent, u16), DEFERRED_EVENTS>,
+    // Tap-hold keys that are held down and were decided to be taps.
+    tapped: [u16; ROWS],
+    // The tap-hold key that is being held with no other key pressed since.
+    retro_tap: Option<(u8, u8)>,
+    // The tap dance waiting for more taps, if any.
+    dance: Option<Dance>,
+    // Tap dance keys that are held down, with the action that they hold.
+    held_dances: [Option<(u8, u8, Keycode)>; HELD_DANCES],
+    report: NkroKeyboardReport,
+    // How many keys are holding each modifier down, so that it is only
+    // released with the last of them.
+    mod_holds: [u8; 8],
+    // Reports that haven't been taken yet, and the last one that was queued.
+    reports: Queue<NkroKeyboardReport, QUEUED_REPORTS>,
+    last_report: NkroKeyboardReport,
+    system_report: ExtraKeyReport,
+    system_reports: Queue<ExtraKeyReport, QUEUED_REPORTS>,
+    last_system_report: ExtraKeyReport,
+    consumer_report: ExtraKeyReport,
+    consumer_reports: Queue<ExtraKeyReport, QUEUED_REPORTS>,
+    last_consumer_report: ExtraKeyReport,
+    mouse: MouseKeys,
+    mouse_reports: Queue<MouseReport, QUEUED_REPORTS>,
+    // System keys that were pressed, for the firmware to act on.
+    system_keys: Queue<SystemKeycode, QUEUED_SYSTEM_KEYS>,
+    host_leds: HostLeds,
+}
+
+impl<const ROWS: usize, const COLS: usize> Keyboard<ROWS, COLS> {
+    pub const fn new(keymap: &'static Keymap<ROWS, COLS>, config: Config) -> Self {
+        assert!(keymap.len() <= MAX_LAYERS);
+        // Columns are bits of a `u16` in the per-row bitmaps.
+        assert!(COLS <= 16);
+        Self {
+            keymap,
+            config,
+            layer_state: LayerState::EMPTY,
+            default_layer_state: LayerState::single(0),
+            oneshot: Oneshot::Idle,
+            source_layers: [[None; COLS]; ROWS],
+            pending: None,
+            deferred: Queue::new(),
+            tapped: [0; ROWS],
+            retro_tap: None,
+            dance: None,
+            held_dances: [None; HELD_DANCES],
+            report: NkroKeyboardReport::new(),
+            mod_holds: [0; 8],
+            reports: Queue::new(),
+            last_report: NkroKeyboardReport::new(),
+            system_report: ExtraKeyReport::system(),
+            system_reports: Queue::new(),
+            last_system_report: ExtraKeyReport::system(),
+            consumer_report: ExtraKeyReport::consumer(),
+            consumer_reports: Queue::new(),
+            last_consumer_report: ExtraKeyReport::consumer(),
+            mouse: MouseKeys::new(config.mouse_cursor, config.mouse_wheel),
+            mouse_reports: Queue::new(),
+            system_keys: Queue::new(),
+            host_leds: HostLeds::from_bits(0),
+        }
+    }
+
+    /// The current state of the keyboard.
+    pub fn report(&self) -> &NkroKeyboardReport {
+        &self.report
+    }
+
+    /// Takes the oldest report that hasn't been sent yet.
+    ///
+    /// Every change of the report is queued, so that e.g. taps are seen by
+    /// the host even if the key is pressed and released at the same time.
+    /// If the queue overflows, two queued reports are merged where that
+    /// doesn't lose a key press or release (see `push_report`). The last
+    /// report is always the current state.
+    pub fn pop_report(&mut self) -> Option<NkroKeyboardReport> {
+        self.reports.pop()
+    }
+
+    /// Takes the oldest system or consumer control report that hasn't been
+    /// sent yet, queued the same way as `pop_report`.
+    ///
+    /// Each kind of report is queued separately, so system reports may be
+    /// taken before consumer reports that were queued earlier.
+    pub fn pop_extra_report(&mut self) -> Option<ExtraKeyReport> {
+        self.system_reports
+            .pop()
+            .or_else(|| self.consumer_reports.pop())
+    }
+
+    /// Takes the oldest mouse report that hasn't been sent yet.
+    ///
+    /// Movements are relative, so every report is a separate move. If the
+    /// queue overflows, moves with the same buttons held are added up (see
+    /// `push_report`).
+    pub fn pop_mouse_report(&mut self) -> Option<MouseReport> {
+        self.mouse_reports.pop()
+    }
+
+    /// Takes the oldest system key press that hasn't been handled yet.
+    ///
+    /// System keys (reset, backlight, ...) act on the hardware, which is up
+    /// to the firmware. Presses beyond the capacity of the queue are dropped.
+    pub fn pop_system_key(&mut self) -> Option<SystemKeycode> {
+        self.system_keys.pop()
+    }
+
+    /// The lock state last reported by the host.
+    pub fn host_leds(&self) -> HostLeds {
+        self.host_leds
+    }
+
+    /// Updates the lock state, from the host's output report.
+    pub fn set_host_leds(&mut self, leds: HostLeds) {
+        self.host_leds = leds;
+    }
+
+    /// The currently active layers, including the default layer and the
+    /// layers activated by `Config::layer_conditions`.
+    pub fn layer_state(&self) -> LayerState {
+        let mut state = self.layer_state.union(self.default_layer_state);
+        for condition in self.config.layer_conditions {
+            if state.contains(condition.layers) {
+                state.set(condition.then);
+            }
+        }
+        state
+    }
+
+    /// Handles timeouts. Should be called regularly, even if there are no key
+    /// events.
+    pub fn tick(&mut self, now: u16) {
+        if let (Oneshot::Armed { layer, since }, Some(timeout)) =
+            (self.oneshot, self.config.oneshot_timeout)
+        {
+            if now.wrapping_sub(since) >= timeout {
+                self.layer_state.clear(layer);
+                self.oneshot = Oneshot::Idle;
+            }
+        }
+        if let Some(report) = self.mouse.tick(now) {
+            push_report(&mut self.mouse_reports, report);
+        }
+        self.tick_dance(now);
+        if let Some(pending) = self.pending {
+            if let Some(decision) = pending.decide::<ROWS>(&self.config, self.deferred.iter(), now)
+            {
+                self.resolve(decision, now);
+            }
+        }
+    }
+
+    pub fn event(&mut self, event: KeyEvent, now: u16) {
+        self.tick(now);
+        if self.pending.is_some() && self.deferred.is_full() {
+            // Out of room to defer any more events.
+            self.resolve(Decision::Hold, now);
+        }
+        if self.pending.is_some() {
+            // Can't fail, there is room now.
+            let _ = self.deferred.push((event, now));
+            self.tick(now);
+        } else {
+            self.process(event, now);
+        }
+    }
+
+    /// Applies the decision of the pending tap-hold key, then processes the
+    /// events that were deferred.
+    fn resolve(&mut self, decision: Decision, now: u16) {
+        let Some(pending) = self.pending.take() else {
+            return;
+        };
+        let (row, col) = (pending.row as usize, pending.col as usize);
+        let keycode = self.source_keycode(row, col);
+        match decision {
+            Decision::Tap => {
+                if let Some(tap_keycode) = tap_keycode(keycode) {
+                    self.tapped[row] |= 1 << col;
+                    self.press(tap_keycode as u8);
+                }
+            }
+            Decision::Hold => {
+                self.set_held(keycode, true);
+                if self.config.retro_tapping {
+                    self.retro_tap = Some((pending.row, pending.col));
+                }
+            }
+        }
+        self.send_report();
+
+        // A deferred event may start another decision, which keeps the rest
+        // of them deferred.
+        while self.pending.is_none() {
+            let Some((event, time)) = self.deferred.pop() else {
+                break;
+            };
+            self.process(event, time);
+        }
+        self.tick(now);
+    }
+
+    fn process(&mut self, event: KeyEvent, now: u16) {
+        let pressed = event.action.is_pressed();
+        let (row, col) = (event.row as usize, event.col as usize);
+        // Before resolving the key, in case the dance changes the layers.
+        // Events can be processed late, after being deferred, so the term may
+        // have passed since the last tick.
+        self.tick_dance(now);
+        if let Some(dance) = self.dance {
+            if pressed && (dance.row, dance.col) != (event.row, event.col) {
+                self.finish_dance(dance.interrupt(), now);
+            }
+        }
+        if pressed {
+            self.source_layers[row][col] = self.resolve_layer(row, col);
+        }
+        let keycode = self.source_keycode(row, col);
+        if !pressed {
+            self.source_layers[row][col] = None;
+        }
+        let retro_tap = self.retro_tap.take();
+        match keycode {
+            Keycode::ModTap(_) | Keycode::LayerTap(_) => {
+                if pressed {
+                    self.pending = Some(Pending {
+                        row: event.row,
+                        col: event.col,
+                        since: now,
+                    });
+                } else if let Some(tap_keycode) = tap_keycode(keycode) {
+                    if (self.tapped[row] & (1 << col)) != 0 {
+                        self.tapped[row] &= !(1 << col);
+                        self.release(tap_keycode as u8);
+                    } else {
+                        self.set_held(keycode, false);
+                        if retro_tap == Some((event.row, event.col)) {
+                            self.send_report();
+                            self.tap(tap_keycode as u8);
+                        }
+                    }
+                }
+            }
+            Keycode::TapDance(index) => self.dance_event(event, index, now),
+            keycode => self.apply(keycode, pressed, now),
+        }
+        if !pressed && retro_tap != Some((event.row, event.col)) {
+            // Releasing some other key doesn't interrupt the retro tap.
+            self.retro_tap = retro_tap;
+        }
+        if !matches!(keycode, Keycode::Layer(_)) {
+            self.update_oneshot(event);
+        }
+        self.send_report();
+        self.send_extra_reports();
+    }
+
+    /// Presses or releases a keycode that acts right away.
+    fn apply(&mut self, keycode: Keycode, pressed: bool, now: u16) {
+        match keycode {
+            Keycode::Consumer(consumer_keycode) => {
+                if pressed {
+                    self.consumer_report.press(consumer_keycode as u16);
+                } else {
+                    self.consumer_report.release(consumer_keycode as u16);
+                }
+            }
+            Keycode::Mouse(mouse_keycode) => {
+                let report = if pressed {
+                    self.mouse.press(mouse_keycode, now)
+                } else {
+                    self.mouse.release(mouse_keycode)
+                };
+                if let Some(report) = report {
+                    push_report(&mut self.mouse_reports, report);
+                }
+            }
+            Keycode::SystemControl(system_keycode) => {
+                if pressed {
+                    self.system_report.press(system_keycode as u16);
+                } else {
+                    self.system_report.release(system_keycode as u16);
+                }
+            }
+            Keycode::Hid(hid_keycode) => {
+                if pressed {
+                    self.press(hid_keycode as u8);
+                } else {
+                    self.release(hid_keycode as u8);
+                }
+            }
+            Keycode::Modified(modified) => {
+                // The modifiers go in a report of their own, before the key
+                // is pressed and after it is released, so the host sees them
+                // applied to the key.
+                if pressed {
+                    for keycode in modified.mods().keycodes() {
+                        self.press(keycode);
+                    }
+                    self.send_report();
+                    self.press(modified.keycode() as u8);
+                } else {
+                    self.release(modified.keycode() as u8);
+                    self.send_report();
+                    for keycode in modified.mods().keycodes() {
+                        self.release(keycode);
+                    }
+                }
+            }
+            Keycode::System(SystemKeycode::None | SystemKeycode::Transparent) => {}
+            Keycode::System(system_keycode) if pressed => {
+                let _ = self.system_keys.push(system_keycode);
+            }
+            Keycode::Layer(layer_keycode) => match layer_keycode.action() {
+                LayerAction::Momentary => {
+                    if pressed {
+                        self.layer_state.set(layer_keycode.layer());
+                    } else {
+                        self.layer_state.clear(layer_keycode.layer());
+                    }
+                }
+                LayerAction::Toggle => {
+                    if pressed {
+                        self.layer_state.toggle(layer_keycode.layer());
+                    }
+                }
+                LayerAction::Oneshot => {
+                    let layer = layer_keycode.layer();
+                    if pressed {
+                        self.layer_state.set(layer);
+                        self.oneshot = Oneshot::Held {
+                            layer,
+                            interrupted: false,
+                        };
+                    } else {
+                        match self.oneshot {
+                            Oneshot::Held {
+                                layer: held,
+                                interrupted,
+                            } if held == layer => {
+                                if interrupted {
+                                    self.layer_state.clear(layer);
+                                    self.oneshot = Oneshot::Idle;
+                                } else {
+                                    self.oneshot = Oneshot::Armed { layer, since: now };
+                                }
+                            }
+                            _ => self.layer_state.clear(layer),
+                        }
+                    }
+                }
+                LayerAction::To => {
+                    if pressed {
+                        self.layer_state = LayerState::single(layer_keycode.layer());
+                        self.oneshot = Oneshot::Idle;
+                    }
+                }
+                LayerAction::Default => {
+                    if pressed {
+                        self.default_layer_state = LayerState::single(layer_keycode.layer());
+                    }
+                }
+            },
+            _ => {}
+        }
+    }
+
+    /// Handles a press or release of a tap dance key.
+    fn dance_event(&mut self, event: KeyEvent, index: u8, now: u16) {
+        let tap_dances = self.config.tap_dances;
+        let Some(tap_dance) = tap_dances.get(usize::from(index)) else {
+            return;
+        };
+        let position = (event.row, event.col);
+        let decision = match &mut self.dance {
+            Some(dance) if (dance.row, dance.col) == position => {
+                if event.action.is_pressed() {
+                    dance.press(tap_dance, now)
+                } else {
+                    dance.release(tap_dance, now)
+                }
+            }
+            _ if event.action.is_pressed() => {
+                let (dance, decision) = Dance::start(event.row, event.col, index, tap_dance, now);
+                self.dance = Some(dance);
+                decision
+            }
+            _ => {
+                // Released after its dance ended.
+                let held = self
+                    .held_dances
+                    .iter_mut()
+                    .find(|held| matches!(held, Some((row, col, _)) if (*row, *col) == position));
+                if let Some(held) = held {
+                    let (_, _, action) = held.take().unwrap();
+                    self.apply(action, false, now);
+                }
+                None
+            }
+        };
+        if let Some(decision) = decision {
+            self.finish_dance(decision, now);
+        }
+    }
+
+    /// Ends the current tap dance if its term has passed.
+    fn tick_dance(&mut self, now: u16) {
+        let Some(dance) = self.dance else {
+            return;
+        };
+        let tap_dances = self.config.tap_dances;
+        let Some(tap_dance) = tap_dances.get(usize::from(dance.index)) else {
+            return;
+        };
+        if let Some(decision) = dance.tick(tap_dance, self.config.tap_dance_term, now) {
+            self.finish_dance(decision, now);
+        }
+    }
+
+    /// Ends the current tap dance, pressing its action if the key is still
+    /// held down, or tapping it otherwise.
+    fn finish_dance(&mut self, decision: tap_dance::Decision, now: u16) {
+        let Some(dance) = self.dance.take() else {
+            return;
+        };
+        let action = match self.config.tap_dances.get(usize::from(dance.index)) {
+            Some(tap_dance) => tap_dance.action(decision.outcome),
+            None => return,
+        };
+        self.apply(action, true, now);
+        let free = self.held_dances.iter_mut().find(|held| held.is_none());
+        match free {
+            Some(free) if decision.held => *free = Some((dance.row, dance.col, action)),
+            _ => {
+                self.send_report();
+                self.send_extra_reports();
+                self.apply(action, false, now);
+            }
+        }
+        self.send_report();
+        self.send_extra_reports();
+    }
+
+    /// Applies or removes the hold action of a tap-hold key.
+    fn set_held(&mut self, keycode: Keycode, held: bool) {
+        match keycode {
+            Keycode::ModTap(mod_tap) => {
+                for keycode in mod_tap.mods().keycodes() {
+                    if held {
+                        self.press(keycode);
+                    } else {
+                        self.release(keycode);
+                    }
+                }
+            }
+            Keycode::LayerTap(layer_tap) => {
+                if held {
+                    self.layer_state.set(layer_tap.layer());
+                } else {
+                    self.layer_state.clear(layer_tap.layer());
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Presses and releases a key, in separate reports.
+    fn tap(&mut self, keycode: u8) {
+        self.press(keycode);
+        self.send_report();
+        self.release(keycode);
+        self.send_report();
+    }
+
+    /// Presses a key in the report. Modifiers are counted, since several keys
+    /// can hold the same one: a modifier key, a mod-tap and a modified key.
+    fn press(&mut self, keycode: u8) {
+        if let Some(holds) = self.mod_holds(keycode) {
+            *holds = holds.saturating_add(1);
+        }
+        self.report.press(keycode);
+    }
+
+    /// Releases a key in the report, unless it is a modifier that another key
+    /// still holds.
+    fn release(&mut self, keycode: u8) {
+        if let Some(holds) = self.mod_holds(keycode) {
+            *holds = holds.saturating_sub(1);
+            if *holds > 0 {
+                return;
+            }
+        }
+        self.report.release(keycode);
+    }
+
+    fn mod_holds(&mut self, keycode: u8) -> Option<&mut u8> {
+        let index = keycode.checked_sub(HidKeycode::LeftControl as u8)?;
+        self.mod_holds.get_mut(usize::from(index))
+    }
+
+    /// Queues the current report if it changed.
+    fn send_report(&mut self) {
+        if self.report.as_bytes() == self.last_report.as_bytes() {
+            return;
+        }
+        self.last_report = self.report;
+        push_report(&mut self.reports, self.report);
+    }
+
+    /// Queues the current system and consumer control reports if they
+    /// changed.
+    fn send_extra_reports(&mut self) {
+        if self.system_report != self.last_system_report {
+            self.last_system_report = self.system_report;
+            push_report(&mut self.system_reports, self.system_report);
+        }
+        if self.consumer_report != self.last_consumer_report {
+            self.last_consumer_report = self.consumer_report;
+            push_report(&mut self.consumer_reports, self.consumer_report);
+        }
+    }
+
+    fn source_keycode(&self, row: usize, col: usize) -> Keycode {
+        match self.source_layers[row][col] {
+            Some(layer) => self.keymap[layer as usize][row][col],
+            None => KC_NO,
+        }
+    }
+
+    fn update_oneshot(&mut self, event: KeyEvent) {
+        if event.action.is_pressed() {
+            match self.oneshot {
+                Oneshot::Held { layer, .. } => {
+                    self.oneshot = Oneshot::Held {
+                        layer,
+                        interrupted: true,
+                    };
+                }
+                Oneshot::Armed { layer, .. } => {
+                    // The key has already been resolved on the one-shot layer
+                    // and will be released from it.
+                    self.layer_state.clear(layer);
+                    self.oneshot = Oneshot::Idle;
+                }
+                Oneshot::Idle => {}
+            }
+        }
+    }
+
+    /// Finds the highest active layer that doesn't have a transparent key at
+    /// the given position.
+    fn resolve_layer(&self, row: usize, col: usize) -> Option<u8> {
+        self.layer_state()
+            .iter_active()
+            .filter(|&layer| (layer as usize) < self.keymap.len())
+            .find(|&layer| self.keymap[layer as usize][row][col] != KC_TRNS)
+    }
+}
+
+/// The key that a tap-hold key sends when it is tapped.
+fn tap_keycode(keycode: Keycode) -> Option<HidKeycode> {
+    match keycode {
+        Keycode::ModTap(mod_tap) => Some(mod_tap.keycode()),
+        Keycode::LayerTap(layer_tap) => Some(layer_tap.keycode()),
+        _ => None,
+    }
+}
+
+/// Queues a report. If the queue is full, the oldest queued report that can
+/// be merged into the one after it is merged to make room. If there is none,
+/// the newest queued report is replaced, so the host still ends up with the
+/// current state.
+fn push_report<T: Report, const N: usize>(reports: &mut Queue<T, N>, report: T) {
+    let Err(mut report) = reports.push(report) else {
+        return;
+    };
+    // The first queued report is left alone, since the one sent before it is
+    // gone.
+    let merge = (1..reports.len()).find_map(|i| {
+        let prev = reports.get(i - 1)?;
+        let current = reports.get(i)?;
+        let next = reports.get(i + 1).unwrap_or(&report);
+        Some((i, T::merge(prev, current, next)?))
+    });
+    match merge {
+        Some((i, merged)) => {
+            reports.remove(i);
+            // The report after the merged one is now at `i`, unless it is the
+            // new one.
+            match reports.get_mut(i) {
+                Some(next) => *next = merged,
+                None => report = merged,
+            }
+            let _ = reports.push(report);
+        }
+        None => {
+            if let Some(back) = reports.back_mut() {
+                *back = T::replace(back, &report);
+            }
+        }
+    }
+}
+
+/// A report that can be merged into the next one when the report queue is
+/// full.
+trait Report: Copy {
+    /// `next` with `report` merged into it, if the host still sees every key
+    /// press and release when it gets `prev` and then the merged report.
+    fn merge(prev: &Self, report: &Self, next: &Self) -> Option<Self>;
+
+    /// `report` in place of `back`, the newest queued report, when nothing can
+    /// be merged.
+    fn replace(_back: &Self, report: &Self) -> Self {
+        *report
+    }
+}
+
+impl Report for NkroKeyboardReport {
+    fn merge(prev: &Self, report: &Self, next: &Self) -> Option<Self> {
+        // A key that changes in `report` and changes back in `next` would be
+        // lost.
+        let lossless = prev
+            .as_bytes()
+            .iter()
+            .zip(report.as_bytes())
+            .zip(next.as_bytes())
+            .all(|((prev, report), next)| (prev ^ report) & (report ^ next) == 0);
+        lossless.then_some(*next)
+    }
+}
+
+impl Report for ExtraKeyReport {
+    fn merge(prev: &Self, report: &Self, next: &Self) -> Option<Self> {
+        // Going from `prev` straight to `next` loses the key in `report`,
+        // unless it is in one of them. If `report` is a release, it is only
+        // lost if `next` presses the same key again.
+        let usage = report.usage();
+        let lossless = usage == prev.usage()
+            || usage == next.usage()
+            || (usage == 0 && prev.usage() != next.usage());
+        lossless.then_some(*next)
+    }
+}
+
+impl Report for MouseReport {
+    fn merge(_prev: &Self, report: &Self, next: &Self) -> Option<Self> {
+        // Only movement with the same buttons held is added up, so that drags
+        // start and end in the same place.
+        if report.buttons != next.buttons {
+            return None;
+        }
+        Some(MouseReport {
+            buttons: next.buttons,
+            x: report.x.checked_add(next.x)?,
+            y: report.y.checked_add(next.y)?,
+            wheel: report.wheel.checked_add(next.wheel)?,
+            pan: report.pan.checked_add(next.pan)?,
+        })
+    }
+
+    fn replace(back: &Self, report: &Self) -> Self {
+        // Keeps as much of the movement as fits.
+        MouseReport {
+            buttons: report.buttons,
+            x: back.x.saturating_add(report.x),
+            y: back.y.saturating_add(report.y),
+            wheel: back.wheel.saturating_add(report.wheel),
+            pan: back.pan.saturating_add(report.pan),
+        }
+    }
+}
@@ -0,0 +1,245 @@
+//! QMK's 16-bit keycode encoding (as of QMK 0.19), used by VIA, Vial and
+//! other QMK tools.
+//!
+//! Not everything maps both ways: QMK reuses part of the HID usage range for
+//! its own keys, only has 4 bits for the layer of a layer tap, and can't mix
+//! left and right modifiers in a mod tap or a modified key.
+
+use core::fmt;
+
+use super::{
+    ConsumerKeycode, HidKeycode, Keycode, LayerAction, LayerKeycode, LayerTapKeycode,
+    ModTapKeycode, ModifiedKeycode, Mods, MouseKeycode, SystemControlKeycode, SystemKeycode,
+};
+
+const KC_NO: u16 = 0x0000;
+const KC_TRANSPARENT: u16 = 0x0001;
+const KC_SYSTEM_POWER: u16 = 0x00a5;
+const KC_MS_UP: u16 = 0x00cd;
+const KC_MS_ACCEL2: u16 = 0x00df;
+const QK_MODS: u16 = 0x0100;
+const QK_MOD_TAP: u16 = 0x2000;
+const QK_LAYER_TAP: u16 = 0x4000;
+const QK_TO: u16 = 0x5200;
+const QK_MOMENTARY: u16 = 0x5220;
+const QK_DEF_LAYER: u16 = 0x5240;
+const QK_TOGGLE_LAYER: u16 = 0x5260;
+const QK_ONE_SHOT_LAYER: u16 = 0x5280;
+const QK_TAP_DANCE: u16 = 0x5700;
+const QK_BACKLIGHT_TOGGLE: u16 = 0x7802;
+const QK_BACKLIGHT_DOWN: u16 = 0x7803;
+const QK_BACKLIGHT_UP: u16 = 0x7804;
+const QK_BACKLIGHT_STEP: u16 = 0x7805;
+const QK_BACKLIGHT_TOGGLE_BREATHING: u16 = 0x7806;
+const QK_BOOTLOADER: u16 = 0x7c00;
+const QK_USER: u16 = 0x7e40;
+
+static CONSUMER: &[(u16, ConsumerKeycode)] = &[
+    (0x00a8, ConsumerKeycode::Mute),
+    (0x00a9, ConsumerKeycode::VolumeIncrement),
+    (0x00aa, ConsumerKeycode::VolumeDecrement),
+    (0x00ab, ConsumerKeycode::ScanNextTrack),
+    (0x00ac, ConsumerKeycode::ScanPreviousTrack),
+    (0x00ad, ConsumerKeycode::Stop),
+    (0x00ae, ConsumerKeycode::PlayPause),
+    (0x00af, ConsumerKeycode::ConsumerControlConfiguration),
+    (0x00b0, ConsumerKeycode::Eject),
+    (0x00b1, ConsumerKeycode::EmailReader),
+    (0x00b2, ConsumerKeycode::Calculator),
+    (0x00b3, ConsumerKeycode::LocalMachineBrowser),
+    (0x00b4, ConsumerKeycode::Search),
+    (0x00b5, ConsumerKeycode::Home),
+    (0x00b6, ConsumerKeycode::Back),
+    (0x00b7, ConsumerKeycode::Forward),
+    (0x00b8, ConsumerKeycode::BrowserStop),
+    (0x00b9, ConsumerKeycode::Refresh),
+    (0x00ba, ConsumerKeycode::Bookmarks),
+    (0x00bb, ConsumerKeycode::FastForward),
+    (0x00bc, ConsumerKeycode::Rewind),
+    (0x00bd, ConsumerKeycode::BrightnessIncrement),
+    (0x00be, ConsumerKeycode::BrightnessDecrement),
+];
+
+static SYSTEM_CONTROL: &[SystemControlKeycode] = &[
+    SystemControlKeycode::PowerDown,
+    SystemControlKeycode::Sleep,
+    SystemControlKeycode::WakeUp,
+];
+
+static SYSTEM: &[(u16, SystemKeycode)] = &[
+    (KC_NO, SystemKeycode::None),
+    (KC_TRANSPARENT, SystemKeycode::Transparent),
+    (QK_BOOTLOADER, SystemKeycode::Reset),
+    (QK_BACKLIGHT_DOWN, SystemKeycode::BacklightDown),
+    (QK_BACKLIGHT_UP, SystemKeycode::BacklightUp),
+    (QK_BACKLIGHT_STEP, SystemKeycode::BacklightStep),
+    (QK_BACKLIGHT_TOGGLE, SystemKeycode::BacklightToggle),
+    (
+        QK_BACKLIGHT_TOGGLE_BREATHING,
+        SystemKeycode::BacklightBreathing,
+    ),
+];
+
+static LAYER_ACTIONS: &[(u16, LayerAction)] = &[
+    (QK_TO, LayerAction::To),
+    (QK_MOMENTARY, LayerAction::Momentary),
+    (QK_DEF_LAYER, LayerAction::Default),
+    (QK_TOGGLE_LAYER, LayerAction::Toggle),
+    (QK_ONE_SHOT_LAYER, LayerAction::Oneshot),
+];
+
+/// Why a keycode can't be converted to or from QMK's encoding.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum QmkKeycodeError {
+    /// The keycode has no QMK encoding, e.g. a layer tap on layer 16 or
+    /// above, or a HID usage that QMK uses for something else.
+    Unrepresentable,
+    /// The QMK keycode is in a range this firmware doesn't support (e.g.
+    /// `QK_LAYER_MOD`), or isn't assigned.
+    Unsupported,
+}
+
+impl fmt::Display for QmkKeycodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Unrepresentable => "keycode has no QMK encoding",
+            Self::Unsupported => "unsupported QMK keycode",
+        })
+    }
+}
+
+/// Whether QMK's basic keycode range uses `code` for the HID usage.
+fn is_basic_hid(code: u8) -> bool {
+    matches!(code, 0x04..=0xa4 | 0xe0..=0xe7)
+}
+
+fn hid_to_qmk(keycode: HidKeycode) -> Result<u16, QmkKeycodeError> {
+    let code = keycode as u8;
+    if is_basic_hid(code) {
+        Ok(code.into())
+    } else {
+        Err(QmkKeycodeError::Unrepresentable)
+    }
+}
+
+fn hid_from_qmk(code: u16) -> Result<HidKeycode, QmkKeycodeError> {
+    match u8::try_from(code) {
+        // Safety: `HidKeycode` has a variant for every basic HID usage.
+        Ok(code) if is_basic_hid(code) => {
+            Ok(unsafe { core::mem::transmute::<u8, HidKeycode>(code) })
+        }
+        _ => Err(QmkKeycodeError::Unsupported),
+    }
+}
+
+/// QMK's 5-bit modifiers: control, shift, alt and GUI, plus a bit that
+/// makes all of them right-hand modifiers.
+fn mods_to_qmk(mods: Mods) -> Result<u16, QmkKeycodeError> {
+    let (left, right) = (mods.bits() & 0x0f, mods.bits() >> 4);
+    match (left, right) {
+        (left, 0) => Ok(left.into()),
+        (0, right) => Ok(0x10 | u16::from(right)),
+        _ => Err(QmkKeycodeError::Unrepresentable),
+    }
+}
+
+fn mods_from_qmk(mods: u16) -> Result<Mods, QmkKeycodeError> {
+    let bits = (mods & 0x0f) as u8;
+    match (mods & 0x10) != 0 {
+        false => Ok(Mods::from_bits(bits)),
+        // Right-hand, but no modifiers.
+        true if bits == 0 => Err(QmkKeycodeError::Unsupported),
+        true => Ok(Mods::from_bits(bits << 4)),
+    }
+}
+
+impl Keycode {
+    /// Encodes the keycode as a QMK keycode.
+    pub fn to_qmk_u16(&self) -> Result<u16, QmkKeycodeError> {
+        match *self {
+            Keycode::Hid(keycode) => hid_to_qmk(keycode),
+            Keycode::Consumer(keycode) => Ok(CONSUMER
+                .iter()
+                .find(|&&(_, k)| k == keycode)
+                .map(|&(code, _)| code)
+                .unwrap()),
+            Keycode::System(keycode) => Ok(SYSTEM
+                .iter()
+                .find(|&&(_, k)| k == keycode)
+                .map(|&(code, _)| code)
+                .unwrap()),
+            Keycode::SystemControl(keycode) => {
+                let index = SYSTEM_CONTROL.iter().position(|&k| k == keycode).unwrap();
+                Ok(KC_SYSTEM_POWER + index as u16)
+            }
+            Keycode::Mouse(keycode) => Ok(KC_MS_UP + keycode as u16),
+            Keycode::Layer(keycode) => {
+                let (base, _) = LAYER_ACTIONS
+                    .iter()
+                    .find(|&&(_, action)| action == keycode.action())
+                    .unwrap();
+                Ok(base | u16::from(keycode.layer()))
+            }
+            Keycode::Modified(keycode) if keycode.mods().is_empty() => {
+                Err(QmkKeycodeError::Unrepresentable)
+            }
+            Keycode::Modified(keycode) => {
+                Ok(mods_to_qmk(keycode.mods())? << 8 | hid_to_qmk(keycode.keycode())?)
+            }
+            Keycode::ModTap(keycode) => {
+                Ok(QK_MOD_TAP | mods_to_qmk(keycode.mods())? << 8 | hid_to_qmk(keycode.keycode())?)
+            }
+            Keycode::LayerTap(keycode) if keycode.layer() < 16 => {
+                Ok(QK_LAYER_TAP | u16::from(keycode.layer()) << 8 | hid_to_qmk(keycode.keycode())?)
+            }
+            Keycode::LayerTap(_) => Err(QmkKeycodeError::Unrepresentable),
+            Keycode::TapDance(index) => Ok(QK_TAP_DANCE | u16::from(index)),
+            Keycode::User(index) => Ok(QK_USER + u16::from(index)),
+        }
+    }
+
+    /// Decodes a QMK keycode.
+    pub fn from_qmk_u16(code: u16) -> Result<Self, QmkKeycodeError> {
+        if let Some(&(_, keycode)) = SYSTEM.iter().find(|&&(c, _)| c == code) {
+            return Ok(keycode.into());
+        }
+        if let Some(&(_, keycode)) = CONSUMER.iter().find(|&&(c, _)| c == code) {
+            return Ok(keycode.into());
+        }
+        match code {
+            KC_SYSTEM_POWER..=0x00a7 => {
+                Ok(SYSTEM_CONTROL[usize::from(code - KC_SYSTEM_POWER)].into())
+            }
+            KC_MS_UP..=KC_MS_ACCEL2 => {
+                // Safety: `MouseKeycode` has a variant for every QMK mouse key,
+                // in the same order.
+                let keycode =
+                    unsafe { core::mem::transmute::<u8, MouseKeycode>((code - KC_MS_UP) as u8) };
+                Ok(keycode.into())
+            }
+            0x0000..=0x00ff => Ok(hid_from_qmk(code)?.into()),
+            QK_MODS..=0x1fff => {
+                let mods = mods_from_qmk(code >> 8)?;
+                Ok(ModifiedKeycode::new(mods, hid_from_qmk(code & 0xff)?).into())
+            }
+            0x2000..=0x3fff => {
+                let mods = mods_from_qmk((code >> 8) & 0x1f)?;
+                Ok(ModTapKeycode::new(mods, hid_from_qmk(code & 0xff)?).into())
+            }
+            0x4000..=0x4fff => {
+                let layer = ((code >> 8) & 0x0f) as u8;
+                Ok(LayerTapKeycode::new(layer, hid_from_qmk(code & 0xff)?).into())
+            }
+            QK_TO..=0x529f => {
+                let &(_, action) = LAYER_ACTIONS
+                    .iter()
+                    .find(|&&(base, _)| base == code & !0x1f)
+                    .unwrap();
+                Ok(LayerKeycode::new(action, (code & 0x1f) as u8).into())
+            }
+            QK_TAP_DANCE..=0x57ff => Ok(Keycode::TapDance(code as u8)),
+            QK_USER..=0x7f3f => Ok(Keycode::User((code - QK_USER) as u8)),
+            _ => Err(QmkKeycodeError::Unsupported),
+        }
+    }
+}
@@ -1,5 +1,10 @@
+mod encoding;
+mod name;
 pub mod qmk;
 
+pub use encoding::QmkKeycodeError;
+pub use name::ParseKeycodeError;
+
 #[derive(Clone, Copy, PartialEq)]
 pub enum KeyAction {
     Pressed,
@@ -19,8 +24,16 @@ impl KeyAction {
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Keycode {
     Hid(HidKeycode),
+    Modified(ModifiedKeycode),
+    Consumer(ConsumerKeycode),
     System(SystemKeycode),
+    SystemControl(SystemControlKeycode),
+    Mouse(MouseKeycode),
     Layer(LayerKeycode),
+    ModTap(ModTapKeycode),
+    LayerTap(LayerTapKeycode),
+    /// A tap dance key, by its index in `Config::tap_dances`.
+    TapDance(u8),
     User(u8),
 }
 
@@ -36,12 +49,48 @@ impl From<HidKeycode> for Keycode {
     }
 }
 
+impl From<ModifiedKeycode> for Keycode {
+    fn from(v: ModifiedKeycode) -> Self {
+        Self::Modified(v)
+    }
+}
+
+impl From<ConsumerKeycode> for Keycode {
+    fn from(v: ConsumerKeycode) -> Self {
+        Self::Consumer(v)
+    }
+}
+
+impl From<SystemControlKeycode> for Keycode {
+    fn from(v: SystemControlKeycode) -> Self {
+        Self::SystemControl(v)
+    }
+}
+
+impl From<MouseKeycode> for Keycode {
+    fn from(v: MouseKeycode) -> Self {
+        Self::Mouse(v)
+    }
+}
+
 impl From<LayerKeycode> for Keycode {
     fn from(v: LayerKeycode) -> Self {
         Self::Layer(v)
     }
 }
 
+impl From<ModTapKeycode> for Keycode {
+    fn from(v: ModTapKeycode) -> Self {
+        Self::ModTap(v)
+    }
+}
+
+impl From<LayerTapKeycode> for Keycode {
+    fn from(v: LayerTapKeycode) -> Self {
+        Self::LayerTap(v)
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 pub enum SystemKeycode {
     None,
@@ -50,6 +99,8 @@ pub enum SystemKeycode {
     BacklightDown,
     BacklightUp,
     BacklightStep,
+    BacklightToggle,
+    BacklightBreathing,
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
@@ -79,6 +130,7 @@ pub enum LayerAction {
     Oneshot,
     Toggle,
     To,
+    Default,
 }
 
 impl LayerAction {
@@ -87,6 +139,7 @@ impl LayerAction {
     const ONESHOT: u8 = 0x40;
     const TOGGLE: u8 = 0x60;
     const TO: u8 = 0x80;
+    const DEFAULT: u8 = 0xa0;
 
     const fn code(&self) -> u8 {
         match self {
@@ -94,6 +147,7 @@ impl LayerAction {
             Self::Oneshot => Self::ONESHOT,
             Self::Toggle => Self::TOGGLE,
             Self::To => Self::TO,
+            Self::Default => Self::DEFAULT,
         }
     }
 
@@ -103,11 +157,118 @@ impl LayerAction {
             Self::ONESHOT => Self::Oneshot,
             Self::TOGGLE => Self::Toggle,
             Self::TO => Self::To,
+            Self::DEFAULT => Self::Default,
             _ => panic!(),
         }
     }
 }
 
+/// A key that activates a layer when held and acts as a regular key when
+/// tapped.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct LayerTapKeycode {
+    layer: u8,
+    keycode: HidKeycode,
+}
+
+impl LayerTapKeycode {
+    pub const fn new(layer: u8, keycode: HidKeycode) -> Self {
+        assert!(layer & LayerKeycode::LAYER_MASK == layer);
+        Self { layer, keycode }
+    }
+
+    pub const fn layer(&self) -> u8 {
+        self.layer
+    }
+
+    pub const fn keycode(&self) -> HidKeycode {
+        self.keycode
+    }
+}
+
+/// A set of modifier keys, in the bit order of the HID modifier byte
+/// (`LeftControl` is bit 0, `RightGui` is bit 7).
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct Mods(u8);
+
+impl Mods {
+    pub const NONE: Self = Self(0);
+    pub const LCTRL: Self = Self(0x01);
+    pub const LSHIFT: Self = Self(0x02);
+    pub const LALT: Self = Self(0x04);
+    pub const LGUI: Self = Self(0x08);
+    pub const RCTRL: Self = Self(0x10);
+    pub const RSHIFT: Self = Self(0x20);
+    pub const RALT: Self = Self(0x40);
+    pub const RGUI: Self = Self(0x80);
+
+    pub const fn from_bits(bits: u8) -> Self {
+        Self(bits)
+    }
+
+    pub const fn bits(&self) -> u8 {
+        self.0
+    }
+
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// The HID keycodes of the modifier keys in this set.
+    pub fn keycodes(self) -> impl Iterator<Item = u8> {
+        (0..8)
+            .filter(move |i| (self.0 & (1 << i)) != 0)
+            .map(|i| HidKeycode::LeftControl as u8 + i)
+    }
+}
+
+/// A key that acts as modifiers when held and as a regular key when tapped.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ModTapKeycode {
+    mods: Mods,
+    keycode: HidKeycode,
+}
+
+impl ModTapKeycode {
+    pub const fn new(mods: Mods, keycode: HidKeycode) -> Self {
+        Self { mods, keycode }
+    }
+
+    pub const fn mods(&self) -> Mods {
+        self.mods
+    }
+
+    pub const fn keycode(&self) -> HidKeycode {
+        self.keycode
+    }
+}
+
+/// A key that is pressed together with modifiers, like Ctrl+C or Shift+1.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ModifiedKeycode {
+    mods: Mods,
+    keycode: HidKeycode,
+}
+
+impl ModifiedKeycode {
+    pub const fn new(mods: Mods, keycode: HidKeycode) -> Self {
+        Self { mods, keycode }
+    }
+
+    pub const fn mods(&self) -> Mods {
+        self.mods
+    }
+
+    pub const fn keycode(&self) -> HidKeycode {
+        self.keycode
+    }
+}
+
 /// Keycodes from the USB HID Usage Tables, Keyboard/Keypad Page (0x07).
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(u8)]
@@ -363,3 +524,70 @@ pub enum HidKeycode {
     RightAlt,
     RightGui,
 }
+
+/// Keycodes from the USB HID Usage Tables, Consumer Page (0x0C).
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum ConsumerKeycode {
+    BrightnessIncrement = 0x06f,
+    BrightnessDecrement = 0x070,
+    FastForward = 0x0b3,
+    Rewind = 0x0b4,
+    ScanNextTrack = 0x0b5,
+    ScanPreviousTrack = 0x0b6,
+    Stop = 0x0b7,
+    Eject = 0x0b8,
+    PlayPause = 0x0cd,
+    Mute = 0x0e2,
+    VolumeIncrement = 0x0e9,
+    VolumeDecrement = 0x0ea,
+    ConsumerControlConfiguration = 0x183,
+    EmailReader = 0x18a,
+    Calculator = 0x192,
+    LocalMachineBrowser = 0x194,
+    Search = 0x221,
+    Home = 0x223,
+    Back = 0x224,
+    Forward = 0x225,
+    BrowserStop = 0x226,
+    Refresh = 0x227,
+    Bookmarks = 0x22a,
+}
+
+/// Keycodes from the USB HID Usage Tables, Generic Desktop Page (0x01),
+/// System Control collection.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u16)]
+pub enum SystemControlKeycode {
+    PowerDown = 0x81,
+    Sleep = 0x82,
+    WakeUp = 0x83,
+}
+
+/// Mouse keys: pointer movement, buttons, wheel and acceleration.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(u8)]
+pub enum MouseKeycode {
+    Up,
+    Down,
+    Left,
+    Right,
+    Button1,
+    Button2,
+    Button3,
+    Button4,
+    Button5,
+    Button6,
+    Button7,
+    Button8,
+    WheelUp,
+    WheelDown,
+    WheelLeft,
+    WheelRight,
+    /// Move at a fixed, slow speed while held.
+    Accel0,
+    /// Move at a fixed, medium speed while held.
+    Accel1,
+    /// Move at a fixed, full speed while held.
+    Accel2,
+}
@@ -0,0 +1,747 @@
+//! Converting keycodes to and from the names used in QMK (and `qmk`), like
+//! `KC_LBRC`, `MO(1)` or `LCTL_T(KC_ESC)`.
+
+use core::{fmt, str::FromStr};
+
+use super::{
+    qmk::*, HidKeycode, Keycode, LayerAction, LayerKeycode, LayerTapKeycode, ModTapKeycode,
+    ModifiedKeycode, Mods,
+};
+use crate::layer::MAX_LAYERS;
+
+/// Every keycode constant in `qmk`. Where a keycode has several names, the
+/// first one is used for `Display`: the short names used by QMK Configurator
+/// come before the long ones.
+static NAMES: &[(&str, Keycode)] = &[
+    ("KC_NO", KC_NO),
+    ("KC_TRNS", KC_TRNS),
+    ("KC_TRANSPARENT", KC_TRANSPARENT),
+    ("RESET", RESET),
+    ("KC_LCTL", KC_LCTL),
+    ("KC_RCTL", KC_RCTL),
+    ("KC_LSFT", KC_LSFT),
+    ("KC_RSFT", KC_RSFT),
+    ("KC_ESC", KC_ESC),
+    ("KC_BSPC", KC_BSPC),
+    ("KC_ENT", KC_ENT),
+    ("KC_DEL", KC_DEL),
+    ("KC_INS", KC_INS),
+    ("KC_CAPS", KC_CAPS),
+    ("KC_CLCK", KC_CLCK),
+    ("KC_RGHT", KC_RGHT),
+    ("KC_PGDN", KC_PGDN),
+    ("KC_PSCR", KC_PSCR),
+    ("KC_SLCK", KC_SLCK),
+    ("KC_PAUS", KC_PAUS),
+    ("KC_BRK", KC_BRK),
+    ("KC_NLCK", KC_NLCK),
+    ("KC_SPC", KC_SPC),
+    ("KC_MINS", KC_MINS),
+    ("KC_EQL", KC_EQL),
+    ("KC_GRV", KC_GRV),
+    ("KC_RBRC", KC_RBRC),
+    ("KC_LBRC", KC_LBRC),
+    ("KC_COMM", KC_COMM),
+    ("KC_BSLS", KC_BSLS),
+    ("KC_SLSH", KC_SLSH),
+    ("KC_SCLN", KC_SCLN),
+    ("KC_QUOT", KC_QUOT),
+    ("KC_APP", KC_APP),
+    ("KC_NUHS", KC_NUHS),
+    ("KC_NUBS", KC_NUBS),
+    ("KC_LCAP", KC_LCAP),
+    ("KC_LNUM", KC_LNUM),
+    ("KC_LSCR", KC_LSCR),
+    ("KC_ERAS", KC_ERAS),
+    ("KC_CLR", KC_CLR),
+    ("KC_ZKHK", KC_ZKHK),
+    ("KC_RO", KC_RO),
+    ("KC_KANA", KC_KANA),
+    ("KC_JYEN", KC_JYEN),
+    ("KC_JPY", KC_JPY),
+    ("KC_HENK", KC_HENK),
+    ("KC_MHEN", KC_MHEN),
+    ("KC_HAEN", KC_HAEN),
+    ("KC_HANJ", KC_HANJ),
+    ("KC_P1", KC_P1),
+    ("KC_P2", KC_P2),
+    ("KC_P3", KC_P3),
+    ("KC_P4", KC_P4),
+    ("KC_P5", KC_P5),
+    ("KC_P6", KC_P6),
+    ("KC_P7", KC_P7),
+    ("KC_P8", KC_P8),
+    ("KC_P9", KC_P9),
+    ("KC_P0", KC_P0),
+    ("KC_P00", KC_P00),
+    ("KC_P000", KC_P000),
+    ("KC_PDOT", KC_PDOT),
+    ("KC_PCMM", KC_PCMM),
+    ("KC_PSLS", KC_PSLS),
+    ("KC_PAST", KC_PAST),
+    ("KC_PMNS", KC_PMNS),
+    ("KC_PPLS", KC_PPLS),
+    ("KC_PEQL", KC_PEQL),
+    ("KC_PENT", KC_PENT),
+    ("KC_EXEC", KC_EXEC),
+    ("KC_SLCT", KC_SLCT),
+    ("KC_AGIN", KC_AGIN),
+    ("KC_PSTE", KC_PSTE),
+    ("KC_ROLL_OVER", KC_ROLL_OVER),
+    ("KC_POST_FAIL", KC_POST_FAIL),
+    ("KC_UNDEFINED", KC_UNDEFINED),
+    ("KC_A", KC_A),
+    ("KC_B", KC_B),
+    ("KC_C", KC_C),
+    ("KC_D", KC_D),
+    ("KC_E", KC_E),
+    ("KC_F", KC_F),
+    ("KC_G", KC_G),
+    ("KC_H", KC_H),
+    ("KC_I", KC_I),
+    ("KC_J", KC_J),
+    ("KC_K", KC_K),
+    ("KC_L", KC_L),
+    ("KC_M", KC_M),
+    ("KC_N", KC_N),
+    ("KC_O", KC_O),
+    ("KC_P", KC_P),
+    ("KC_Q", KC_Q),
+    ("KC_R", KC_R),
+    ("KC_S", KC_S),
+    ("KC_T", KC_T),
+    ("KC_U", KC_U),
+    ("KC_V", KC_V),
+    ("KC_W", KC_W),
+    ("KC_X", KC_X),
+    ("KC_Y", KC_Y),
+    ("KC_Z", KC_Z),
+    ("KC_1", KC_1),
+    ("KC_2", KC_2),
+    ("KC_3", KC_3),
+    ("KC_4", KC_4),
+    ("KC_5", KC_5),
+    ("KC_6", KC_6),
+    ("KC_7", KC_7),
+    ("KC_8", KC_8),
+    ("KC_9", KC_9),
+    ("KC_0", KC_0),
+    ("KC_ENTER", KC_ENTER),
+    ("KC_ESCAPE", KC_ESCAPE),
+    ("KC_BSPACE", KC_BSPACE),
+    ("KC_TAB", KC_TAB),
+    ("KC_SPACE", KC_SPACE),
+    ("KC_MINUS", KC_MINUS),
+    ("KC_EQUAL", KC_EQUAL),
+    ("KC_LBRACKET", KC_LBRACKET),
+    ("KC_RBRACKET", KC_RBRACKET),
+    ("KC_BSLASH", KC_BSLASH),
+    ("KC_NONUS_HASH", KC_NONUS_HASH),
+    ("KC_SCOLON", KC_SCOLON),
+    ("KC_QUOTE", KC_QUOTE),
+    ("KC_GRAVE", KC_GRAVE),
+    ("KC_COMMA", KC_COMMA),
+    ("KC_DOT", KC_DOT),
+    ("KC_SLASH", KC_SLASH),
+    ("KC_CAPSLOCK", KC_CAPSLOCK),
+    ("KC_F1", KC_F1),
+    ("KC_F2", KC_F2),
+    ("KC_F3", KC_F3),
+    ("KC_F4", KC_F4),
+    ("KC_F5", KC_F5),
+    ("KC_F6", KC_F6),
+    ("KC_F7", KC_F7),
+    ("KC_F8", KC_F8),
+    ("KC_F9", KC_F9),
+    ("KC_F10", KC_F10),
+    ("KC_F11", KC_F11),
+    ("KC_F12", KC_F12),
+    ("KC_PSCREEN", KC_PSCREEN),
+    ("KC_SCROLLLOCK", KC_SCROLLLOCK),
+    ("KC_PAUSE", KC_PAUSE),
+    ("KC_INSERT", KC_INSERT),
+    ("KC_HOME", KC_HOME),
+    ("KC_PGUP", KC_PGUP),
+    ("KC_DELETE", KC_DELETE),
+    ("KC_END", KC_END),
+    ("KC_PGDOWN", KC_PGDOWN),
+    ("KC_RIGHT", KC_RIGHT),
+    ("KC_LEFT", KC_LEFT),
+    ("KC_DOWN", KC_DOWN),
+    ("KC_UP", KC_UP),
+    ("KC_NUMLOCK", KC_NUMLOCK),
+    ("KC_KP_SLASH", KC_KP_SLASH),
+    ("KC_KP_ASTERISK", KC_KP_ASTERISK),
+    ("KC_KP_MINUS", KC_KP_MINUS),
+    ("KC_KP_PLUS", KC_KP_PLUS),
+    ("KC_KP_ENTER", KC_KP_ENTER),
+    ("KC_KP_1", KC_KP_1),
+    ("KC_KP_2", KC_KP_2),
+    ("KC_KP_3", KC_KP_3),
+    ("KC_KP_4", KC_KP_4),
+    ("KC_KP_5", KC_KP_5),
+    ("KC_KP_6", KC_KP_6),
+    ("KC_KP_7", KC_KP_7),
+    ("KC_KP_8", KC_KP_8),
+    ("KC_KP_9", KC_KP_9),
+    ("KC_KP_0", KC_KP_0),
+    ("KC_KP_DOT", KC_KP_DOT),
+    ("KC_NONUS_BSLASH", KC_NONUS_BSLASH),
+    ("KC_APPLICATION", KC_APPLICATION),
+    ("KC_POWER", KC_POWER),
+    ("KC_KP_EQUAL", KC_KP_EQUAL),
+    ("KC_F13", KC_F13),
+    ("KC_F14", KC_F14),
+    ("KC_F15", KC_F15),
+    ("KC_F16", KC_F16),
+    ("KC_F17", KC_F17),
+    ("KC_F18", KC_F18),
+    ("KC_F19", KC_F19),
+    ("KC_F20", KC_F20),
+    ("KC_F21", KC_F21),
+    ("KC_F22", KC_F22),
+    ("KC_F23", KC_F23),
+    ("KC_F24", KC_F24),
+    ("KC_EXECUTE", KC_EXECUTE),
+    ("KC_HELP", KC_HELP),
+    ("KC_MENU", KC_MENU),
+    ("KC_SELECT", KC_SELECT),
+    ("KC_STOP", KC_STOP),
+    ("KC_AGAIN", KC_AGAIN),
+    ("KC_UNDO", KC_UNDO),
+    ("KC_CUT", KC_CUT),
+    ("KC_COPY", KC_COPY),
+    ("KC_PASTE", KC_PASTE),
+    ("KC_FIND", KC_FIND),
+    ("KC__MUTE", KC__MUTE),
+    ("KC__VOLUP", KC__VOLUP),
+    ("KC__VOLDOWN", KC__VOLDOWN),
+    ("KC_LOCKING_CAPS", KC_LOCKING_CAPS),
+    ("KC_LOCKING_NUM", KC_LOCKING_NUM),
+    ("KC_LOCKING_SCROLL", KC_LOCKING_SCROLL),
+    ("KC_KP_COMMA", KC_KP_COMMA),
+    ("KC_KP_EQUAL_AS400", KC_KP_EQUAL_AS400),
+    ("KC_INT1", KC_INT1),
+    ("KC_INT2", KC_INT2),
+    ("KC_INT3", KC_INT3),
+    ("KC_INT4", KC_INT4),
+    ("KC_INT5", KC_INT5),
+    ("KC_INT6", KC_INT6),
+    ("KC_INT7", KC_INT7),
+    ("KC_INT8", KC_INT8),
+    ("KC_INT9", KC_INT9),
+    ("KC_LANG1", KC_LANG1),
+    ("KC_LANG2", KC_LANG2),
+    ("KC_LANG3", KC_LANG3),
+    ("KC_LANG4", KC_LANG4),
+    ("KC_LANG5", KC_LANG5),
+    ("KC_LANG6", KC_LANG6),
+    ("KC_LANG7", KC_LANG7),
+    ("KC_LANG8", KC_LANG8),
+    ("KC_LANG9", KC_LANG9),
+    ("KC_ALT_ERASE", KC_ALT_ERASE),
+    ("KC_SYSREQ", KC_SYSREQ),
+    ("KC_CANCEL", KC_CANCEL),
+    ("KC_CLEAR", KC_CLEAR),
+    ("KC_PRIOR", KC_PRIOR),
+    ("KC_RETURN", KC_RETURN),
+    ("KC_SEPARATOR", KC_SEPARATOR),
+    ("KC_OUT", KC_OUT),
+    ("KC_OPER", KC_OPER),
+    ("KC_CLEAR_AGAIN", KC_CLEAR_AGAIN),
+    ("KC_CRSEL", KC_CRSEL),
+    ("KC_EXSEL", KC_EXSEL),
+    ("KC_KP_00", KC_KP_00),
+    ("KC_KP_000", KC_KP_000),
+    ("KC_THOUSANDS_SEPARATOR", KC_THOUSANDS_SEPARATOR),
+    ("KC_DECIMAL_SEPARATOR", KC_DECIMAL_SEPARATOR),
+    ("KC_CURRENCY_UNIT", KC_CURRENCY_UNIT),
+    ("KC_CURRENCY_SUB_UNIT", KC_CURRENCY_SUB_UNIT),
+    ("KC_KP_LPAREN", KC_KP_LPAREN),
+    ("KC_KP_RPAREN", KC_KP_RPAREN),
+    ("KC_KP_LCBRACKET", KC_KP_LCBRACKET),
+    ("KC_KP_RCBRACKET", KC_KP_RCBRACKET),
+    ("KC_KP_TAB", KC_KP_TAB),
+    ("KC_KP_BSPACE", KC_KP_BSPACE),
+    ("KC_KP_A", KC_KP_A),
+    ("KC_KP_B", KC_KP_B),
+    ("KC_KP_C", KC_KP_C),
+    ("KC_KP_D", KC_KP_D),
+    ("KC_KP_E", KC_KP_E),
+    ("KC_KP_F", KC_KP_F),
+    ("KC_KP_XOR", KC_KP_XOR),
+    ("KC_KP_HAT", KC_KP_HAT),
+    ("KC_KP_PERC", KC_KP_PERC),
+    ("KC_KP_LT", KC_KP_LT),
+    ("KC_KP_GT", KC_KP_GT),
+    ("KC_KP_AND", KC_KP_AND),
+    ("KC_KP_LAZYAND", KC_KP_LAZYAND),
+    ("KC_KP_OR", KC_KP_OR),
+    ("KC_KP_LAZYOR", KC_KP_LAZYOR),
+    ("KC_KP_COLON", KC_KP_COLON),
+    ("KC_KP_HASH", KC_KP_HASH),
+    ("KC_KP_SPACE", KC_KP_SPACE),
+    ("KC_ATMARK", KC_ATMARK),
+    ("KC_KP_EXCLAMATION", KC_KP_EXCLAMATION),
+    ("KC_KP_MEM_STORE", KC_KP_MEM_STORE),
+    ("KC_KP_MEM_RECALL", KC_KP_MEM_RECALL),
+    ("KC_KP_MEM_CLEAR", KC_KP_MEM_CLEAR),
+    ("KC_KP_MEM_ADD", KC_KP_MEM_ADD),
+    ("KC_KP_MEM_SUB", KC_KP_MEM_SUB),
+    ("KC_KP_MEM_MUL", KC_KP_MEM_MUL),
+    ("KC_KP_MEM_DIV", KC_KP_MEM_DIV),
+    ("KC_KP_PLUS_MINUS", KC_KP_PLUS_MINUS),
+    ("KC_KP_CLEAR", KC_KP_CLEAR),
+    ("KC_KP_CLEAR_ENTRY", KC_KP_CLEAR_ENTRY),
+    ("KC_KP_BINARY", KC_KP_BINARY),
+    ("KC_KP_OCTAL", KC_KP_OCTAL),
+    ("KC_KP_DECIMAL", KC_KP_DECIMAL),
+    ("KC_KP_HEXADECIMAL", KC_KP_HEXADECIMAL),
+    ("KC_LCTRL", KC_LCTRL),
+    ("KC_LSHIFT", KC_LSHIFT),
+    ("KC_LALT", KC_LALT),
+    ("KC_LGUI", KC_LGUI),
+    ("KC_RCTRL", KC_RCTRL),
+    ("KC_RSHIFT", KC_RSHIFT),
+    ("KC_RALT", KC_RALT),
+    ("KC_RGUI", KC_RGUI),
+    ("KC_TILD", KC_TILD),
+    ("KC_EXLM", KC_EXLM),
+    ("KC_AT", KC_AT),
+    ("KC_HASH", KC_HASH),
+    ("KC_DLR", KC_DLR),
+    ("KC_PERC", KC_PERC),
+    ("KC_CIRC", KC_CIRC),
+    ("KC_AMPR", KC_AMPR),
+    ("KC_ASTR", KC_ASTR),
+    ("KC_LPRN", KC_LPRN),
+    ("KC_RPRN", KC_RPRN),
+    ("KC_UNDS", KC_UNDS),
+    ("KC_PLUS", KC_PLUS),
+    ("KC_LCBR", KC_LCBR),
+    ("KC_RCBR", KC_RCBR),
+    ("KC_PIPE", KC_PIPE),
+    ("KC_COLN", KC_COLN),
+    ("KC_DQUO", KC_DQUO),
+    ("KC_LABK", KC_LABK),
+    ("KC_RABK", KC_RABK),
+    ("KC_QUES", KC_QUES),
+    ("KC_TILDE", KC_TILDE),
+    ("KC_EXCLAIM", KC_EXCLAIM),
+    ("KC_DOLLAR", KC_DOLLAR),
+    ("KC_PERCENT", KC_PERCENT),
+    ("KC_CIRCUMFLEX", KC_CIRCUMFLEX),
+    ("KC_AMPERSAND", KC_AMPERSAND),
+    ("KC_ASTERISK", KC_ASTERISK),
+    ("KC_LEFT_PAREN", KC_LEFT_PAREN),
+    ("KC_RIGHT_PAREN", KC_RIGHT_PAREN),
+    ("KC_UNDERSCORE", KC_UNDERSCORE),
+    ("KC_LEFT_CURLY_BRACE", KC_LEFT_CURLY_BRACE),
+    ("KC_RIGHT_CURLY_BRACE", KC_RIGHT_CURLY_BRACE),
+    ("KC_COLON", KC_COLON),
+    ("KC_DOUBLE_QUOTE", KC_DOUBLE_QUOTE),
+    ("KC_LEFT_ANGLE_BRACKET", KC_LEFT_ANGLE_BRACKET),
+    ("KC_RIGHT_ANGLE_BRACKET", KC_RIGHT_ANGLE_BRACKET),
+    ("KC_QUESTION", KC_QUESTION),
+    ("KC_DQT", KC_DQT),
+    ("KC_LT", KC_LT),
+    ("KC_GT", KC_GT),
+    ("BL_DEC", BL_DEC),
+    ("BL_INC", BL_INC),
+    ("BL_STEP", BL_STEP),
+    ("BL_TOGG", BL_TOGG),
+    ("BL_BRTG", BL_BRTG),
+    ("KC_MS_U", KC_MS_U),
+    ("KC_MS_UP", KC_MS_UP),
+    ("KC_MS_D", KC_MS_D),
+    ("KC_MS_DOWN", KC_MS_DOWN),
+    ("KC_MS_L", KC_MS_L),
+    ("KC_MS_LEFT", KC_MS_LEFT),
+    ("KC_MS_R", KC_MS_R),
+    ("KC_MS_RIGHT", KC_MS_RIGHT),
+    ("KC_BTN1", KC_BTN1),
+    ("KC_MS_BTN1", KC_MS_BTN1),
+    ("KC_BTN2", KC_BTN2),
+    ("KC_MS_BTN2", KC_MS_BTN2),
+    ("KC_BTN3", KC_BTN3),
+    ("KC_MS_BTN3", KC_MS_BTN3),
+    ("KC_BTN4", KC_BTN4),
+    ("KC_MS_BTN4", KC_MS_BTN4),
+    ("KC_BTN5", KC_BTN5),
+    ("KC_MS_BTN5", KC_MS_BTN5),
+    ("KC_BTN6", KC_BTN6),
+    ("KC_MS_BTN6", KC_MS_BTN6),
+    ("KC_BTN7", KC_BTN7),
+    ("KC_MS_BTN7", KC_MS_BTN7),
+    ("KC_BTN8", KC_BTN8),
+    ("KC_MS_BTN8", KC_MS_BTN8),
+    ("KC_WH_U", KC_WH_U),
+    ("KC_MS_WH_UP", KC_MS_WH_UP),
+    ("KC_WH_D", KC_WH_D),
+    ("KC_MS_WH_DOWN", KC_MS_WH_DOWN),
+    ("KC_WH_L", KC_WH_L),
+    ("KC_MS_WH_LEFT", KC_MS_WH_LEFT),
+    ("KC_WH_R", KC_WH_R),
+    ("KC_MS_WH_RIGHT", KC_MS_WH_RIGHT),
+    ("KC_ACL0", KC_ACL0),
+    ("KC_MS_ACCEL0", KC_MS_ACCEL0),
+    ("KC_ACL1", KC_ACL1),
+    ("KC_MS_ACCEL1", KC_MS_ACCEL1),
+    ("KC_ACL2", KC_ACL2),
+    ("KC_MS_ACCEL2", KC_MS_ACCEL2),
+    ("KC_PWR", KC_PWR),
+    ("KC_SYSTEM_POWER", KC_SYSTEM_POWER),
+    ("KC_SLEP", KC_SLEP),
+    ("KC_SYSTEM_SLEEP", KC_SYSTEM_SLEEP),
+    ("KC_WAKE", KC_WAKE),
+    ("KC_SYSTEM_WAKE", KC_SYSTEM_WAKE),
+    ("KC_MUTE", KC_MUTE),
+    ("KC_AUDIO_MUTE", KC_AUDIO_MUTE),
+    ("KC_VOLU", KC_VOLU),
+    ("KC_AUDIO_VOL_UP", KC_AUDIO_VOL_UP),
+    ("KC_VOLD", KC_VOLD),
+    ("KC_AUDIO_VOL_DOWN", KC_AUDIO_VOL_DOWN),
+    ("KC_MNXT", KC_MNXT),
+    ("KC_MEDIA_NEXT_TRACK", KC_MEDIA_NEXT_TRACK),
+    ("KC_MPRV", KC_MPRV),
+    ("KC_MEDIA_PREV_TRACK", KC_MEDIA_PREV_TRACK),
+    ("KC_MFFD", KC_MFFD),
+    ("KC_MEDIA_FAST_FORWARD", KC_MEDIA_FAST_FORWARD),
+    ("KC_MRWD", KC_MRWD),
+    ("KC_MEDIA_REWIND", KC_MEDIA_REWIND),
+    ("KC_MSTP", KC_MSTP),
+    ("KC_MEDIA_STOP", KC_MEDIA_STOP),
+    ("KC_MPLY", KC_MPLY),
+    ("KC_MEDIA_PLAY_PAUSE", KC_MEDIA_PLAY_PAUSE),
+    ("KC_EJCT", KC_EJCT),
+    ("KC_MEDIA_EJECT", KC_MEDIA_EJECT),
+    ("KC_MSEL", KC_MSEL),
+    ("KC_MEDIA_SELECT", KC_MEDIA_SELECT),
+    ("KC_MAIL", KC_MAIL),
+    ("KC_CALC", KC_CALC),
+    ("KC_CALCULATOR", KC_CALCULATOR),
+    ("KC_MYCM", KC_MYCM),
+    ("KC_MY_COMPUTER", KC_MY_COMPUTER),
+    ("KC_WSCH", KC_WSCH),
+    ("KC_WWW_SEARCH", KC_WWW_SEARCH),
+    ("KC_WHOM", KC_WHOM),
+    ("KC_WWW_HOME", KC_WWW_HOME),
+    ("KC_WBAK", KC_WBAK),
+    ("KC_WWW_BACK", KC_WWW_BACK),
+    ("KC_WFWD", KC_WFWD),
+    ("KC_WWW_FORWARD", KC_WWW_FORWARD),
+    ("KC_WSTP", KC_WSTP),
+    ("KC_WWW_STOP", KC_WWW_STOP),
+    ("KC_WREF", KC_WREF),
+    ("KC_WWW_REFRESH", KC_WWW_REFRESH),
+    ("KC_WFAV", KC_WFAV),
+    ("KC_WWW_FAVORITES", KC_WWW_FAVORITES),
+    ("KC_BRIU", KC_BRIU),
+    ("KC_BRTI", KC_BRTI),
+    ("KC_BRIGHTNESS_UP", KC_BRIGHTNESS_UP),
+    ("KC_BRID", KC_BRID),
+    ("KC_BRTD", KC_BRTD),
+    ("KC_BRIGHTNESS_DOWN", KC_BRIGHTNESS_DOWN),
+    ("XXXXXXX", XXXXXXX),
+    ("_______", _______),
+];
+
+static LAYER_ACTIONS: &[(&str, LayerAction)] = &[
+    ("MO", LayerAction::Momentary),
+    ("OSL", LayerAction::Oneshot),
+    ("TG", LayerAction::Toggle),
+    ("TO", LayerAction::To),
+    ("DF", LayerAction::Default),
+];
+
+static MODS: &[(&str, Mods)] = &[
+    ("MOD_HYPR", MOD_HYPR),
+    ("MOD_MEH", MOD_MEH),
+    ("MOD_LCTL", MOD_LCTL),
+    ("MOD_LSFT", MOD_LSFT),
+    ("MOD_LALT", MOD_LALT),
+    ("MOD_LGUI", MOD_LGUI),
+    ("MOD_RCTL", MOD_RCTL),
+    ("MOD_RSFT", MOD_RSFT),
+    ("MOD_RALT", MOD_RALT),
+    ("MOD_RGUI", MOD_RGUI),
+];
+
+/// The functions that wrap a keycode in modifiers, in order of preference for
+/// `Display`.
+static MODIFIERS: &[(&str, Mods)] = &[
+    ("LCTL", MOD_LCTL),
+    ("LSFT", MOD_LSFT),
+    ("LALT", MOD_LALT),
+    ("LGUI", MOD_LGUI),
+    ("RCTL", MOD_RCTL),
+    ("RSFT", MOD_RSFT),
+    ("RALT", MOD_RALT),
+    ("RGUI", MOD_RGUI),
+    ("LCS", MOD_LCTL.union(MOD_LSFT)),
+    ("LCA", MOD_LCTL.union(MOD_LALT)),
+    ("LSA", MOD_LSFT.union(MOD_LALT)),
+    ("LSG", MOD_LSFT.union(MOD_LGUI)),
+    ("LAG", MOD_LALT.union(MOD_LGUI)),
+    ("RCS", MOD_RCTL.union(MOD_RSFT)),
+    ("RSA", MOD_RSFT.union(MOD_RALT)),
+    ("RSG", MOD_RSFT.union(MOD_RGUI)),
+    ("RAG", MOD_RALT.union(MOD_RGUI)),
+    ("LCAG", MOD_LCTL.union(MOD_LALT).union(MOD_LGUI)),
+    ("MEH", MOD_MEH),
+    ("HYPR", MOD_HYPR),
+    ("C", MOD_LCTL),
+    ("S", MOD_LSFT),
+    ("A", MOD_LALT),
+    ("G", MOD_LGUI),
+    ("LOPT", MOD_LALT),
+    ("LCMD", MOD_LGUI),
+    ("LWIN", MOD_LGUI),
+    ("ROPT", MOD_RALT),
+    ("ALGR", MOD_RALT),
+    ("RCMD", MOD_RGUI),
+    ("RWIN", MOD_RGUI),
+    ("SGUI", MOD_LSFT.union(MOD_LGUI)),
+    ("SCMD", MOD_LSFT.union(MOD_LGUI)),
+    ("SWIN", MOD_LSFT.union(MOD_LGUI)),
+    ("SAGR", MOD_RSFT.union(MOD_RALT)),
+];
+
+/// The `MT()` shorthands, in order of preference for `Display`.
+static MOD_TAPS: &[(&str, Mods)] = &[
+    ("LCTL_T", MOD_LCTL),
+    ("RCTL_T", MOD_RCTL),
+    ("LSFT_T", MOD_LSFT),
+    ("RSFT_T", MOD_RSFT),
+    ("LALT_T", MOD_LALT),
+    ("RALT_T", MOD_RALT),
+    ("LGUI_T", MOD_LGUI),
+    ("RGUI_T", MOD_RGUI),
+    ("C_S_T", MOD_LCTL.union(MOD_LSFT)),
+    ("RCS_T", MOD_RCTL.union(MOD_RSFT)),
+    ("LCA_T", MOD_LCTL.union(MOD_LALT)),
+    ("RCA_T", MOD_RCTL.union(MOD_RALT)),
+    ("LSA_T", MOD_LSFT.union(MOD_LALT)),
+    ("RSA_T", MOD_RSFT.union(MOD_RALT)),
+    ("LSG_T", MOD_LSFT.union(MOD_LGUI)),
+    ("LCAG_T", MOD_LCTL.union(MOD_LALT).union(MOD_LGUI)),
+    ("RCAG_T", MOD_RCTL.union(MOD_RALT).union(MOD_RGUI)),
+    ("MEH_T", MOD_MEH),
+    ("HYPR_T", MOD_HYPR),
+    ("CTL_T", MOD_LCTL),
+    ("SFT_T", MOD_LSFT),
+    ("ALT_T", MOD_LALT),
+    ("LOPT_T", MOD_LALT),
+    ("ROPT_T", MOD_RALT),
+    ("ALGR_T", MOD_RALT),
+    ("OPT_T", MOD_LALT),
+    ("GUI_T", MOD_LGUI),
+    ("LCMD_T", MOD_LGUI),
+    ("RCMD_T", MOD_RGUI),
+    ("CMD_T", MOD_LGUI),
+    ("LWIN_T", MOD_LGUI),
+    ("RWIN_T", MOD_RGUI),
+    ("WIN_T", MOD_LGUI),
+    ("LCS_T", MOD_LCTL.union(MOD_LSFT)),
+    ("SAGR_T", MOD_RSFT.union(MOD_RALT)),
+    ("SGUI_T", MOD_LSFT.union(MOD_LGUI)),
+    ("ALL_T", MOD_HYPR),
+];
+
+/// Prefix of the names of `Keycode::User` keycodes (`QK_USER_0` and up).
+const USER_PREFIX: &str = "QK_USER_";
+
+/// Why a string isn't a valid keycode.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum ParseKeycodeError {
+    /// Not a known keycode or function name.
+    UnknownName,
+    /// A function's argument is out of range or of the wrong kind, e.g.
+    /// `MO(32)` or `LT(1, MO(2))`.
+    InvalidArgument,
+    /// Unbalanced parentheses or a wrong number of arguments.
+    Syntax,
+}
+
+impl fmt::Display for ParseKeycodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::UnknownName => "unknown keycode",
+            Self::InvalidArgument => "invalid keycode argument",
+            Self::Syntax => "malformed keycode",
+        })
+    }
+}
+
+fn lookup<T: Copy>(table: &[(&str, T)], name: &str) -> Option<T> {
+    table
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|&(_, value)| value)
+}
+
+fn name_of<T: PartialEq>(table: &[(&'static str, T)], value: &T) -> Option<&'static str> {
+    table
+        .iter()
+        .find(|(_, v)| v == value)
+        .map(|&(name, _)| name)
+}
+
+fn parse_layer(s: &str) -> Result<u8, ParseKeycodeError> {
+    match s.trim().parse::<u8>() {
+        Ok(layer) if usize::from(layer) < MAX_LAYERS => Ok(layer),
+        _ => Err(ParseKeycodeError::InvalidArgument),
+    }
+}
+
+fn parse_hid(s: &str) -> Result<HidKeycode, ParseKeycodeError> {
+    match s.parse()? {
+        Keycode::Hid(keycode) => Ok(keycode),
+        _ => Err(ParseKeycodeError::InvalidArgument),
+    }
+}
+
+fn split_args(args: &str) -> Result<(&str, &str), ParseKeycodeError> {
+    args.split_once(',').ok_or(ParseKeycodeError::Syntax)
+}
+
+impl FromStr for Keycode {
+    type Err = ParseKeycodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let Some((function, args)) = s.split_once('(') else {
+            if let Some(index) = s.strip_prefix(USER_PREFIX) {
+                return index
+                    .parse()
+                    .map(Keycode::User)
+                    .map_err(|_| ParseKeycodeError::InvalidArgument);
+            }
+            return lookup(NAMES, s).ok_or(ParseKeycodeError::UnknownName);
+        };
+        let function = function.trim();
+        let args = args.strip_suffix(')').ok_or(ParseKeycodeError::Syntax)?;
+
+        if let Some(action) = lookup(LAYER_ACTIONS, function) {
+            return Ok(LayerKeycode::new(action, parse_layer(args)?).into());
+        }
+        if let Some(mods) = lookup(MODIFIERS, function) {
+            // Modifier functions nest, e.g. `LCTL(LSFT(KC_A))` or `LCTL(KC_EXLM)`.
+            return match args.parse()? {
+                Keycode::Hid(keycode) => Ok(ModifiedKeycode::new(mods, keycode).into()),
+                Keycode::Modified(keycode) => {
+                    Ok(ModifiedKeycode::new(mods.union(keycode.mods()), keycode.keycode()).into())
+                }
+                _ => Err(ParseKeycodeError::InvalidArgument),
+            };
+        }
+        if let Some(mods) = lookup(MOD_TAPS, function) {
+            return Ok(ModTapKeycode::new(mods, parse_hid(args)?).into());
+        }
+        match function {
+            "TD" => args
+                .trim()
+                .parse()
+                .map(Keycode::TapDance)
+                .map_err(|_| ParseKeycodeError::InvalidArgument),
+            "LT" => {
+                let (layer, tap) = split_args(args)?;
+                Ok(LayerTapKeycode::new(parse_layer(layer)?, parse_hid(tap)?).into())
+            }
+            "MT" => {
+                let (mods, tap) = split_args(args)?;
+                let mods = mods
+                    .parse()
+                    .map_err(|_| ParseKeycodeError::InvalidArgument)?;
+                Ok(ModTapKeycode::new(mods, parse_hid(tap)?).into())
+            }
+            _ => Err(ParseKeycodeError::UnknownName),
+        }
+    }
+}
+
+impl fmt::Display for Keycode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Keycode::Layer(keycode) => {
+                let function = name_of(LAYER_ACTIONS, &keycode.action()).unwrap();
+                write!(f, "{}({})", function, keycode.layer())
+            }
+            Keycode::LayerTap(keycode) => {
+                write!(
+                    f,
+                    "LT({}, {})",
+                    keycode.layer(),
+                    Keycode::Hid(keycode.keycode())
+                )
+            }
+            Keycode::ModTap(keycode) => {
+                let tap = Keycode::Hid(keycode.keycode());
+                match name_of(MOD_TAPS, &keycode.mods()) {
+                    Some(function) => write!(f, "{}({})", function, tap),
+                    None => write!(f, "MT({}, {})", keycode.mods(), tap),
+                }
+            }
+            Keycode::Modified(keycode) if name_of(NAMES, self).is_none() => {
+                let key = Keycode::Hid(keycode.keycode());
+                if let Some(function) = name_of(MODIFIERS, &keycode.mods()) {
+                    return write!(f, "{}({})", function, key);
+                }
+                // No function for this combination, so nest the single ones.
+                let (mut rest, mut depth) = (keycode.mods().bits(), 0);
+                for (function, mods) in MODIFIERS {
+                    if mods.bits().count_ones() == 1 && (rest & mods.bits()) != 0 {
+                        write!(f, "{}(", function)?;
+                        rest &= !mods.bits();
+                        depth += 1;
+                    }
+                }
+                write!(f, "{}", key)?;
+                for _ in 0..depth {
+                    f.write_str(")")?;
+                }
+                Ok(())
+            }
+            Keycode::TapDance(index) => write!(f, "TD({})", index),
+            Keycode::User(index) => write!(f, "{}{}", USER_PREFIX, index),
+            keycode => f.write_str(name_of(NAMES, &keycode).expect("every keycode has a name")),
+        }
+    }
+}
+
+/// Parses a `|`-separated list of modifier names, like
+/// `MOD_LCTL | MOD_LSFT`. `0` is no modifiers.
+impl FromStr for Mods {
+    type Err = ParseKeycodeError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.trim() == "0" {
+            return Ok(Mods::NONE);
+        }
+        s.split('|').try_fold(Mods::NONE, |mods, name| {
+            lookup(MODS, name.trim())
+                .map(|m| mods.union(m))
+                .ok_or(ParseKeycodeError::UnknownName)
+        })
+    }
+}
+
+impl fmt::Display for Mods {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if let Some(name) = name_of(MODS, self) {
+            return f.write_str(name);
+        }
+        if self.is_empty() {
+            return f.write_str("0");
+        }
+        let mut separator = "";
+        for (name, mods) in MODS {
+            // Single modifiers only; the combined ones were checked above.
+            if mods.bits().count_ones() == 1 && (self.bits() & mods.bits()) != 0 {
+                write!(f, "{}{}", separator, name)?;
+                separator = " | ";
+            }
+        }
+        Ok(())
+    }
+}
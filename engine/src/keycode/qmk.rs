@@ -2,7 +2,10 @@
 
 //! Aliases for keycodes based on the names used in QMK/TMK.
 
-use super::{HidKeycode, Keycode, LayerAction, LayerKeycode, SystemKeycode};
+use super::{
+    ConsumerKeycode, HidKeycode, Keycode, LayerAction, LayerKeycode, LayerTapKeycode,
+    ModTapKeycode, ModifiedKeycode, Mods, MouseKeycode, SystemControlKeycode, SystemKeycode,
+};
 
 pub const fn MO(layer: u8) -> Keycode {
     Keycode::Layer(LayerKeycode::new(LayerAction::Momentary, layer))
@@ -20,6 +23,344 @@ pub const fn TO(layer: u8) -> Keycode {
     Keycode::Layer(LayerKeycode::new(LayerAction::To, layer))
 }
 
+pub const fn DF(layer: u8) -> Keycode {
+    Keycode::Layer(LayerKeycode::new(LayerAction::Default, layer))
+}
+
+pub const fn LT(layer: u8, kc: Keycode) -> Keycode {
+    Keycode::LayerTap(LayerTapKeycode::new(layer, hid(kc)))
+}
+
+pub const fn TD(index: u8) -> Keycode {
+    Keycode::TapDance(index)
+}
+
+// Modifier masks. Unlike QMK's 5-bit encoding, these are full HID modifier
+// bitmasks, so left and right modifiers can be mixed.
+pub const MOD_LCTL: Mods = Mods::LCTRL;
+pub const MOD_LSFT: Mods = Mods::LSHIFT;
+pub const MOD_LALT: Mods = Mods::LALT;
+pub const MOD_LGUI: Mods = Mods::LGUI;
+pub const MOD_RCTL: Mods = Mods::RCTRL;
+pub const MOD_RSFT: Mods = Mods::RSHIFT;
+pub const MOD_RALT: Mods = Mods::RALT;
+pub const MOD_RGUI: Mods = Mods::RGUI;
+pub const MOD_MEH: Mods = MOD_LCTL.union(MOD_LSFT).union(MOD_LALT);
+pub const MOD_HYPR: Mods = MOD_MEH.union(MOD_LGUI);
+
+const fn hid(keycode: Keycode) -> HidKeycode {
+    match keycode {
+        Keycode::Hid(hid_keycode) => hid_keycode,
+        _ => panic!("expected a HID keycode"),
+    }
+}
+
+// Modifier keys https://docs.qmk.fm/#/feature_advanced_keycodes?id=modifier-keys
+// They can be nested, e.g. `LCTL(LSFT(KC_A))`.
+const fn modified(mods: Mods, kc: Keycode) -> Keycode {
+    match kc {
+        Keycode::Hid(keycode) => Keycode::Modified(ModifiedKeycode::new(mods, keycode)),
+        Keycode::Modified(modified) => Keycode::Modified(ModifiedKeycode::new(
+            modified.mods().union(mods),
+            modified.keycode(),
+        )),
+        _ => panic!("expected a HID keycode"),
+    }
+}
+
+pub const fn LCTL(kc: Keycode) -> Keycode {
+    modified(MOD_LCTL, kc)
+}
+
+pub const fn LSFT(kc: Keycode) -> Keycode {
+    modified(MOD_LSFT, kc)
+}
+
+pub const fn LALT(kc: Keycode) -> Keycode {
+    modified(MOD_LALT, kc)
+}
+
+pub const fn LGUI(kc: Keycode) -> Keycode {
+    modified(MOD_LGUI, kc)
+}
+
+pub const fn LOPT(kc: Keycode) -> Keycode {
+    modified(MOD_LALT, kc)
+}
+
+pub const fn LCMD(kc: Keycode) -> Keycode {
+    modified(MOD_LGUI, kc)
+}
+
+pub const fn LWIN(kc: Keycode) -> Keycode {
+    modified(MOD_LGUI, kc)
+}
+
+pub const fn RCTL(kc: Keycode) -> Keycode {
+    modified(MOD_RCTL, kc)
+}
+
+pub const fn RSFT(kc: Keycode) -> Keycode {
+    modified(MOD_RSFT, kc)
+}
+
+pub const fn RALT(kc: Keycode) -> Keycode {
+    modified(MOD_RALT, kc)
+}
+
+pub const fn RGUI(kc: Keycode) -> Keycode {
+    modified(MOD_RGUI, kc)
+}
+
+pub const fn ROPT(kc: Keycode) -> Keycode {
+    modified(MOD_RALT, kc)
+}
+
+pub const fn ALGR(kc: Keycode) -> Keycode {
+    modified(MOD_RALT, kc)
+}
+
+pub const fn RCMD(kc: Keycode) -> Keycode {
+    modified(MOD_RGUI, kc)
+}
+
+pub const fn RWIN(kc: Keycode) -> Keycode {
+    modified(MOD_RGUI, kc)
+}
+
+pub const fn C(kc: Keycode) -> Keycode {
+    modified(MOD_LCTL, kc)
+}
+
+pub const fn S(kc: Keycode) -> Keycode {
+    modified(MOD_LSFT, kc)
+}
+
+pub const fn A(kc: Keycode) -> Keycode {
+    modified(MOD_LALT, kc)
+}
+
+pub const fn G(kc: Keycode) -> Keycode {
+    modified(MOD_LGUI, kc)
+}
+
+pub const fn LCS(kc: Keycode) -> Keycode {
+    modified(MOD_LCTL.union(MOD_LSFT), kc)
+}
+
+pub const fn LCA(kc: Keycode) -> Keycode {
+    modified(MOD_LCTL.union(MOD_LALT), kc)
+}
+
+pub const fn LSA(kc: Keycode) -> Keycode {
+    modified(MOD_LSFT.union(MOD_LALT), kc)
+}
+
+pub const fn LSG(kc: Keycode) -> Keycode {
+    modified(MOD_LSFT.union(MOD_LGUI), kc)
+}
+
+pub const fn SGUI(kc: Keycode) -> Keycode {
+    modified(MOD_LSFT.union(MOD_LGUI), kc)
+}
+
+pub const fn SCMD(kc: Keycode) -> Keycode {
+    modified(MOD_LSFT.union(MOD_LGUI), kc)
+}
+
+pub const fn SWIN(kc: Keycode) -> Keycode {
+    modified(MOD_LSFT.union(MOD_LGUI), kc)
+}
+
+pub const fn LAG(kc: Keycode) -> Keycode {
+    modified(MOD_LALT.union(MOD_LGUI), kc)
+}
+
+pub const fn RCS(kc: Keycode) -> Keycode {
+    modified(MOD_RCTL.union(MOD_RSFT), kc)
+}
+
+pub const fn RSA(kc: Keycode) -> Keycode {
+    modified(MOD_RSFT.union(MOD_RALT), kc)
+}
+
+pub const fn SAGR(kc: Keycode) -> Keycode {
+    modified(MOD_RSFT.union(MOD_RALT), kc)
+}
+
+pub const fn RSG(kc: Keycode) -> Keycode {
+    modified(MOD_RSFT.union(MOD_RGUI), kc)
+}
+
+pub const fn RAG(kc: Keycode) -> Keycode {
+    modified(MOD_RALT.union(MOD_RGUI), kc)
+}
+
+pub const fn LCAG(kc: Keycode) -> Keycode {
+    modified(MOD_LCTL.union(MOD_LALT).union(MOD_LGUI), kc)
+}
+
+pub const fn MEH(kc: Keycode) -> Keycode {
+    modified(MOD_MEH, kc)
+}
+
+pub const fn HYPR(kc: Keycode) -> Keycode {
+    modified(MOD_HYPR, kc)
+}
+
+// Mod-tap keys https://docs.qmk.fm/#/mod_tap
+pub const fn MT(mods: Mods, kc: Keycode) -> Keycode {
+    Keycode::ModTap(ModTapKeycode::new(mods, hid(kc)))
+}
+
+pub const fn LCTL_T(kc: Keycode) -> Keycode {
+    MT(MOD_LCTL, kc)
+}
+
+pub const fn RCTL_T(kc: Keycode) -> Keycode {
+    MT(MOD_RCTL, kc)
+}
+
+pub const fn CTL_T(kc: Keycode) -> Keycode {
+    LCTL_T(kc)
+}
+
+pub const fn LSFT_T(kc: Keycode) -> Keycode {
+    MT(MOD_LSFT, kc)
+}
+
+pub const fn RSFT_T(kc: Keycode) -> Keycode {
+    MT(MOD_RSFT, kc)
+}
+
+pub const fn SFT_T(kc: Keycode) -> Keycode {
+    LSFT_T(kc)
+}
+
+pub const fn LALT_T(kc: Keycode) -> Keycode {
+    MT(MOD_LALT, kc)
+}
+
+pub const fn RALT_T(kc: Keycode) -> Keycode {
+    MT(MOD_RALT, kc)
+}
+
+pub const fn ALT_T(kc: Keycode) -> Keycode {
+    LALT_T(kc)
+}
+
+pub const fn LOPT_T(kc: Keycode) -> Keycode {
+    LALT_T(kc)
+}
+
+pub const fn ROPT_T(kc: Keycode) -> Keycode {
+    RALT_T(kc)
+}
+
+pub const fn ALGR_T(kc: Keycode) -> Keycode {
+    RALT_T(kc)
+}
+
+pub const fn OPT_T(kc: Keycode) -> Keycode {
+    LALT_T(kc)
+}
+
+pub const fn LGUI_T(kc: Keycode) -> Keycode {
+    MT(MOD_LGUI, kc)
+}
+
+pub const fn RGUI_T(kc: Keycode) -> Keycode {
+    MT(MOD_RGUI, kc)
+}
+
+pub const fn GUI_T(kc: Keycode) -> Keycode {
+    LGUI_T(kc)
+}
+
+pub const fn LCMD_T(kc: Keycode) -> Keycode {
+    LGUI_T(kc)
+}
+
+pub const fn RCMD_T(kc: Keycode) -> Keycode {
+    RGUI_T(kc)
+}
+
+pub const fn CMD_T(kc: Keycode) -> Keycode {
+    LGUI_T(kc)
+}
+
+pub const fn LWIN_T(kc: Keycode) -> Keycode {
+    LGUI_T(kc)
+}
+
+pub const fn RWIN_T(kc: Keycode) -> Keycode {
+    RGUI_T(kc)
+}
+
+pub const fn WIN_T(kc: Keycode) -> Keycode {
+    LGUI_T(kc)
+}
+
+pub const fn C_S_T(kc: Keycode) -> Keycode {
+    MT(MOD_LCTL.union(MOD_LSFT), kc)
+}
+
+pub const fn LCS_T(kc: Keycode) -> Keycode {
+    C_S_T(kc)
+}
+
+pub const fn RCS_T(kc: Keycode) -> Keycode {
+    MT(MOD_RCTL.union(MOD_RSFT), kc)
+}
+
+pub const fn LCA_T(kc: Keycode) -> Keycode {
+    MT(MOD_LCTL.union(MOD_LALT), kc)
+}
+
+pub const fn RCA_T(kc: Keycode) -> Keycode {
+    MT(MOD_RCTL.union(MOD_RALT), kc)
+}
+
+pub const fn LSA_T(kc: Keycode) -> Keycode {
+    MT(MOD_LSFT.union(MOD_LALT), kc)
+}
+
+pub const fn RSA_T(kc: Keycode) -> Keycode {
+    MT(MOD_RSFT.union(MOD_RALT), kc)
+}
+
+pub const fn SAGR_T(kc: Keycode) -> Keycode {
+    RSA_T(kc)
+}
+
+pub const fn LSG_T(kc: Keycode) -> Keycode {
+    MT(MOD_LSFT.union(MOD_LGUI), kc)
+}
+
+pub const fn SGUI_T(kc: Keycode) -> Keycode {
+    LSG_T(kc)
+}
+
+pub const fn LCAG_T(kc: Keycode) -> Keycode {
+    MT(MOD_LCTL.union(MOD_LALT).union(MOD_LGUI), kc)
+}
+
+pub const fn RCAG_T(kc: Keycode) -> Keycode {
+    MT(MOD_RCTL.union(MOD_RALT).union(MOD_RGUI), kc)
+}
+
+pub const fn MEH_T(kc: Keycode) -> Keycode {
+    MT(MOD_MEH, kc)
+}
+
+pub const fn HYPR_T(kc: Keycode) -> Keycode {
+    MT(MOD_HYPR, kc)
+}
+
+pub const fn ALL_T(kc: Keycode) -> Keycode {
+    HYPR_T(kc)
+}
+
 pub const KC_NO: Keycode = Keycode::System(SystemKeycode::None);
 pub const KC_TRANSPARENT: Keycode = Keycode::System(SystemKeycode::Transparent);
 pub const RESET: Keycode = Keycode::System(SystemKeycode::Reset);
@@ -103,51 +444,6 @@ pub const KC_SLCT: Keycode = KC_SELECT;
 pub const KC_AGIN: Keycode = KC_AGAIN;
 pub const KC_PSTE: Keycode = KC_PASTE;
 /*TODO
-    /* Mousekey */
-    pub const KC_MS_U: Keycode = KC_MS_UP;
-    pub const KC_MS_D: Keycode = KC_MS_DOWN;
-    pub const KC_MS_L: Keycode = KC_MS_LEFT;
-    pub const KC_MS_R: Keycode = KC_MS_RIGHT;
-    pub const KC_BTN1: Keycode = KC_MS_BTN1;
-    pub const KC_BTN2: Keycode = KC_MS_BTN2;
-    pub const KC_BTN3: Keycode = KC_MS_BTN3;
-    pub const KC_BTN4: Keycode = KC_MS_BTN4;
-    pub const KC_BTN5: Keycode = KC_MS_BTN5;
-    pub const KC_WH_U: Keycode = KC_MS_WH_UP;
-    pub const KC_WH_D: Keycode = KC_MS_WH_DOWN;
-    pub const KC_WH_L: Keycode = KC_MS_WH_LEFT;
-    pub const KC_WH_R: Keycode = KC_MS_WH_RIGHT;
-    pub const KC_ACL0: Keycode = KC_MS_ACCEL0;
-    pub const KC_ACL1: Keycode = KC_MS_ACCEL1;
-    pub const KC_ACL2: Keycode = KC_MS_ACCEL2;
-    /* Sytem Control */
-    pub const KC_PWR : Keycode = KC_SYSTEM_POWER;
-    pub const KC_SLEP: Keycode = KC_SYSTEM_SLEEP;
-    pub const KC_WAKE: Keycode = KC_SYSTEM_WAKE;
-    /* Consumer Page */
-    pub const KC_MUTE: Keycode = KC_AUDIO_MUTE;
-    pub const KC_VOLU: Keycode = KC_AUDIO_VOL_UP;
-    pub const KC_VOLD: Keycode = KC_AUDIO_VOL_DOWN;
-    pub const KC_MNXT: Keycode = KC_MEDIA_NEXT_TRACK;
-    pub const KC_MPRV: Keycode = KC_MEDIA_PREV_TRACK;
-    pub const KC_MFFD: Keycode = KC_MEDIA_FAST_FORWARD;
-    pub const KC_MRWD: Keycode = KC_MEDIA_REWIND;
-    pub const KC_MSTP: Keycode = KC_MEDIA_STOP;
-    pub const KC_MPLY: Keycode = KC_MEDIA_PLAY_PAUSE;
-    pub const KC_EJCT: Keycode = KC_MEDIA_EJECT;
-    pub const KC_MSEL: Keycode = KC_MEDIA_SELECT;
-    pub const KC_MAIL: Keycode = KC_MAIL;
-    pub const KC_CALC: Keycode = KC_CALCULATOR;
-    pub const KC_MYCM: Keycode = KC_MY_COMPUTER;
-    pub const KC_WSCH: Keycode = KC_WWW_SEARCH;
-    pub const KC_WHOM: Keycode = KC_WWW_HOME;
-    pub const KC_WBAK: Keycode = KC_WWW_BACK;
-    pub const KC_WFWD: Keycode = KC_WWW_FORWARD;
-    pub const KC_WSTP: Keycode = KC_WWW_STOP;
-    pub const KC_WREF: Keycode = KC_WWW_REFRESH;
-    pub const KC_WFAV: Keycode = KC_WWW_FAVORITES;
-    pub const KC_BRTI: Keycode = KC_BRIGHTNESS_INC;
-    pub const KC_BRTD: Keycode = KC_BRIGHTNESS_DEC;
     /* Jump to bootloader */
     pub const KC_BTLD: Keycode = KC_BOOTLOADER;
 */
@@ -378,3 +674,146 @@ pub const KC_RGUI: Keycode = Keycode::Hid(HidKeycode::RightGui);
 pub const BL_DEC: Keycode = Keycode::System(SystemKeycode::BacklightDown);
 pub const BL_INC: Keycode = Keycode::System(SystemKeycode::BacklightUp);
 pub const BL_STEP: Keycode = Keycode::System(SystemKeycode::BacklightStep);
+pub const BL_TOGG: Keycode = Keycode::System(SystemKeycode::BacklightToggle);
+pub const BL_BRTG: Keycode = Keycode::System(SystemKeycode::BacklightBreathing);
+
+// Mouse keycodes https://docs.qmk.fm/#/feature_mouse_keys
+pub const KC_MS_UP: Keycode = Keycode::Mouse(MouseKeycode::Up);
+pub const KC_MS_DOWN: Keycode = Keycode::Mouse(MouseKeycode::Down);
+pub const KC_MS_LEFT: Keycode = Keycode::Mouse(MouseKeycode::Left);
+pub const KC_MS_RIGHT: Keycode = Keycode::Mouse(MouseKeycode::Right);
+pub const KC_MS_BTN1: Keycode = Keycode::Mouse(MouseKeycode::Button1);
+pub const KC_MS_BTN2: Keycode = Keycode::Mouse(MouseKeycode::Button2);
+pub const KC_MS_BTN3: Keycode = Keycode::Mouse(MouseKeycode::Button3);
+pub const KC_MS_BTN4: Keycode = Keycode::Mouse(MouseKeycode::Button4);
+pub const KC_MS_BTN5: Keycode = Keycode::Mouse(MouseKeycode::Button5);
+pub const KC_MS_BTN6: Keycode = Keycode::Mouse(MouseKeycode::Button6);
+pub const KC_MS_BTN7: Keycode = Keycode::Mouse(MouseKeycode::Button7);
+pub const KC_MS_BTN8: Keycode = Keycode::Mouse(MouseKeycode::Button8);
+pub const KC_MS_WH_UP: Keycode = Keycode::Mouse(MouseKeycode::WheelUp);
+pub const KC_MS_WH_DOWN: Keycode = Keycode::Mouse(MouseKeycode::WheelDown);
+pub const KC_MS_WH_LEFT: Keycode = Keycode::Mouse(MouseKeycode::WheelLeft);
+pub const KC_MS_WH_RIGHT: Keycode = Keycode::Mouse(MouseKeycode::WheelRight);
+pub const KC_MS_ACCEL0: Keycode = Keycode::Mouse(MouseKeycode::Accel0);
+pub const KC_MS_ACCEL1: Keycode = Keycode::Mouse(MouseKeycode::Accel1);
+pub const KC_MS_ACCEL2: Keycode = Keycode::Mouse(MouseKeycode::Accel2);
+pub const KC_MS_U: Keycode = KC_MS_UP;
+pub const KC_MS_D: Keycode = KC_MS_DOWN;
+pub const KC_MS_L: Keycode = KC_MS_LEFT;
+pub const KC_MS_R: Keycode = KC_MS_RIGHT;
+pub const KC_BTN1: Keycode = KC_MS_BTN1;
+pub const KC_BTN2: Keycode = KC_MS_BTN2;
+pub const KC_BTN3: Keycode = KC_MS_BTN3;
+pub const KC_BTN4: Keycode = KC_MS_BTN4;
+pub const KC_BTN5: Keycode = KC_MS_BTN5;
+pub const KC_BTN6: Keycode = KC_MS_BTN6;
+pub const KC_BTN7: Keycode = KC_MS_BTN7;
+pub const KC_BTN8: Keycode = KC_MS_BTN8;
+pub const KC_WH_U: Keycode = KC_MS_WH_UP;
+pub const KC_WH_D: Keycode = KC_MS_WH_DOWN;
+pub const KC_WH_L: Keycode = KC_MS_WH_LEFT;
+pub const KC_WH_R: Keycode = KC_MS_WH_RIGHT;
+pub const KC_ACL0: Keycode = KC_MS_ACCEL0;
+pub const KC_ACL1: Keycode = KC_MS_ACCEL1;
+pub const KC_ACL2: Keycode = KC_MS_ACCEL2;
+
+// System control keycodes https://docs.qmk.fm/#/keycodes?id=basic-keycodes
+pub const KC_SYSTEM_POWER: Keycode = Keycode::SystemControl(SystemControlKeycode::PowerDown);
+pub const KC_SYSTEM_SLEEP: Keycode = Keycode::SystemControl(SystemControlKeycode::Sleep);
+pub const KC_SYSTEM_WAKE: Keycode = Keycode::SystemControl(SystemControlKeycode::WakeUp);
+pub const KC_PWR: Keycode = KC_SYSTEM_POWER;
+pub const KC_SLEP: Keycode = KC_SYSTEM_SLEEP;
+pub const KC_WAKE: Keycode = KC_SYSTEM_WAKE;
+
+// Consumer page keycodes https://docs.qmk.fm/#/keycodes?id=basic-keycodes
+pub const KC_AUDIO_MUTE: Keycode = Keycode::Consumer(ConsumerKeycode::Mute);
+pub const KC_AUDIO_VOL_UP: Keycode = Keycode::Consumer(ConsumerKeycode::VolumeIncrement);
+pub const KC_AUDIO_VOL_DOWN: Keycode = Keycode::Consumer(ConsumerKeycode::VolumeDecrement);
+pub const KC_MEDIA_NEXT_TRACK: Keycode = Keycode::Consumer(ConsumerKeycode::ScanNextTrack);
+pub const KC_MEDIA_PREV_TRACK: Keycode = Keycode::Consumer(ConsumerKeycode::ScanPreviousTrack);
+pub const KC_MEDIA_FAST_FORWARD: Keycode = Keycode::Consumer(ConsumerKeycode::FastForward);
+pub const KC_MEDIA_REWIND: Keycode = Keycode::Consumer(ConsumerKeycode::Rewind);
+pub const KC_MEDIA_STOP: Keycode = Keycode::Consumer(ConsumerKeycode::Stop);
+pub const KC_MEDIA_PLAY_PAUSE: Keycode = Keycode::Consumer(ConsumerKeycode::PlayPause);
+pub const KC_MEDIA_EJECT: Keycode = Keycode::Consumer(ConsumerKeycode::Eject);
+pub const KC_MEDIA_SELECT: Keycode =
+    Keycode::Consumer(ConsumerKeycode::ConsumerControlConfiguration);
+pub const KC_MAIL: Keycode = Keycode::Consumer(ConsumerKeycode::EmailReader);
+pub const KC_CALCULATOR: Keycode = Keycode::Consumer(ConsumerKeycode::Calculator);
+pub const KC_MY_COMPUTER: Keycode = Keycode::Consumer(ConsumerKeycode::LocalMachineBrowser);
+pub const KC_WWW_SEARCH: Keycode = Keycode::Consumer(ConsumerKeycode::Search);
+pub const KC_WWW_HOME: Keycode = Keycode::Consumer(ConsumerKeycode::Home);
+pub const KC_WWW_BACK: Keycode = Keycode::Consumer(ConsumerKeycode::Back);
+pub const KC_WWW_FORWARD: Keycode = Keycode::Consumer(ConsumerKeycode::Forward);
+pub const KC_WWW_STOP: Keycode = Keycode::Consumer(ConsumerKeycode::BrowserStop);
+pub const KC_WWW_REFRESH: Keycode = Keycode::Consumer(ConsumerKeycode::Refresh);
+pub const KC_WWW_FAVORITES: Keycode = Keycode::Consumer(ConsumerKeycode::Bookmarks);
+pub const KC_BRIGHTNESS_UP: Keycode = Keycode::Consumer(ConsumerKeycode::BrightnessIncrement);
+pub const KC_BRIGHTNESS_DOWN: Keycode = Keycode::Consumer(ConsumerKeycode::BrightnessDecrement);
+pub const KC_MUTE: Keycode = KC_AUDIO_MUTE;
+pub const KC_VOLU: Keycode = KC_AUDIO_VOL_UP;
+pub const KC_VOLD: Keycode = KC_AUDIO_VOL_DOWN;
+pub const KC_MNXT: Keycode = KC_MEDIA_NEXT_TRACK;
+pub const KC_MPRV: Keycode = KC_MEDIA_PREV_TRACK;
+pub const KC_MFFD: Keycode = KC_MEDIA_FAST_FORWARD;
+pub const KC_MRWD: Keycode = KC_MEDIA_REWIND;
+pub const KC_MSTP: Keycode = KC_MEDIA_STOP;
+pub const KC_MPLY: Keycode = KC_MEDIA_PLAY_PAUSE;
+pub const KC_EJCT: Keycode = KC_MEDIA_EJECT;
+pub const KC_MSEL: Keycode = KC_MEDIA_SELECT;
+pub const KC_CALC: Keycode = KC_CALCULATOR;
+pub const KC_MYCM: Keycode = KC_MY_COMPUTER;
+pub const KC_WSCH: Keycode = KC_WWW_SEARCH;
+pub const KC_WHOM: Keycode = KC_WWW_HOME;
+pub const KC_WBAK: Keycode = KC_WWW_BACK;
+pub const KC_WFWD: Keycode = KC_WWW_FORWARD;
+pub const KC_WSTP: Keycode = KC_WWW_STOP;
+pub const KC_WREF: Keycode = KC_WWW_REFRESH;
+pub const KC_WFAV: Keycode = KC_WWW_FAVORITES;
+pub const KC_BRIU: Keycode = KC_BRIGHTNESS_UP;
+pub const KC_BRID: Keycode = KC_BRIGHTNESS_DOWN;
+pub const KC_BRTI: Keycode = KC_BRIGHTNESS_UP;
+pub const KC_BRTD: Keycode = KC_BRIGHTNESS_DOWN;
+
+// US ANSI shifted keycodes https://docs.qmk.fm/#/keycodes_us_ansi_shifted
+pub const KC_TILD: Keycode = S(KC_GRV);
+pub const KC_EXLM: Keycode = S(KC_1);
+pub const KC_AT: Keycode = S(KC_2);
+pub const KC_HASH: Keycode = S(KC_3);
+pub const KC_DLR: Keycode = S(KC_4);
+pub const KC_PERC: Keycode = S(KC_5);
+pub const KC_CIRC: Keycode = S(KC_6);
+pub const KC_AMPR: Keycode = S(KC_7);
+pub const KC_ASTR: Keycode = S(KC_8);
+pub const KC_LPRN: Keycode = S(KC_9);
+pub const KC_RPRN: Keycode = S(KC_0);
+pub const KC_UNDS: Keycode = S(KC_MINS);
+pub const KC_PLUS: Keycode = S(KC_EQL);
+pub const KC_LCBR: Keycode = S(KC_LBRC);
+pub const KC_RCBR: Keycode = S(KC_RBRC);
+pub const KC_PIPE: Keycode = S(KC_BSLS);
+pub const KC_COLN: Keycode = S(KC_SCLN);
+pub const KC_DQUO: Keycode = S(KC_QUOT);
+pub const KC_LABK: Keycode = S(KC_COMM);
+pub const KC_RABK: Keycode = S(KC_DOT);
+pub const KC_QUES: Keycode = S(KC_SLSH);
+pub const KC_TILDE: Keycode = KC_TILD;
+pub const KC_EXCLAIM: Keycode = KC_EXLM;
+pub const KC_DOLLAR: Keycode = KC_DLR;
+pub const KC_PERCENT: Keycode = KC_PERC;
+pub const KC_CIRCUMFLEX: Keycode = KC_CIRC;
+pub const KC_AMPERSAND: Keycode = KC_AMPR;
+pub const KC_ASTERISK: Keycode = KC_ASTR;
+pub const KC_LEFT_PAREN: Keycode = KC_LPRN;
+pub const KC_RIGHT_PAREN: Keycode = KC_RPRN;
+pub const KC_UNDERSCORE: Keycode = KC_UNDS;
+pub const KC_LEFT_CURLY_BRACE: Keycode = KC_LCBR;
+pub const KC_RIGHT_CURLY_BRACE: Keycode = KC_RCBR;
+pub const KC_COLON: Keycode = KC_COLN;
+pub const KC_DOUBLE_QUOTE: Keycode = KC_DQUO;
+pub const KC_LEFT_ANGLE_BRACKET: Keycode = KC_LABK;
+pub const KC_RIGHT_ANGLE_BRACKET: Keycode = KC_RABK;
+pub const KC_QUESTION: Keycode = KC_QUES;
+pub const KC_DQT: Keycode = KC_DQUO;
+pub const KC_LT: Keycode = KC_LABK;
+pub const KC_GT: Keycode = KC_RABK;
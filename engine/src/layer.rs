@@ -0,0 +1,72 @@
+//! The set of active layers.
+
+/// The maximum number of layers in a keymap.
+pub const MAX_LAYERS: usize = 32;
+
+/// A set of layers, one bit per layer.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Default, Debug)]
+#[repr(transparent)]
+pub struct LayerState(u32);
+
+impl LayerState {
+    /// No layers active.
+    pub const EMPTY: Self = Self(0);
+
+    pub const fn from_bits(bits: u32) -> Self {
+        Self(bits)
+    }
+
+    pub const fn bits(&self) -> u32 {
+        self.0
+    }
+
+    /// A state with only the given layer active.
+    pub const fn single(layer: u8) -> Self {
+        Self(1 << layer)
+    }
+
+    /// This state with the given layer also active.
+    pub const fn with(self, layer: u8) -> Self {
+        Self(self.0 | (1 << layer))
+    }
+
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    pub const fn is_active(&self, layer: u8) -> bool {
+        (self.0 & (1 << layer)) != 0
+    }
+
+    /// Whether all layers in `other` are active in this state.
+    pub const fn contains(&self, other: Self) -> bool {
+        (self.0 & other.0) == other.0
+    }
+
+    pub fn set(&mut self, layer: u8) {
+        self.0 |= 1 << layer;
+    }
+
+    pub fn clear(&mut self, layer: u8) {
+        self.0 &= !(1 << layer);
+    }
+
+    pub fn toggle(&mut self, layer: u8) {
+        self.0 ^= 1 << layer;
+    }
+
+    /// The highest layer that is active, if any.
+    pub const fn highest_active(&self) -> Option<u8> {
+        match self.0 {
+            0 => None,
+            bits => Some(31 - bits.leading_zeros() as u8),
+        }
+    }
+
+    /// The active layers, from highest to lowest.
+    pub fn iter_active(self) -> impl Iterator<Item = u8> {
+        (0..MAX_LAYERS as u8)
+            .rev()
+            .filter(move |&layer| self.is_active(layer))
+    }
+}
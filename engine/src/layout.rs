@@ -0,0 +1,113 @@
+//! Macros for writing keymaps.
+//!
+//! `keymap!` declares the layers of a keymap, along with a constant for the
+//! index of each layer:
+//!
+//! ```ignore
+//! keymap! {
+//!     pub static LAYERS: [[Keycode; 12]; 4] = {
+//!         BASE => layout_planck_mit![...],
+//!         LOWER => layout_planck_mit![...],
+//!     };
+//! }
+//! ```
+//!
+//! The Planck layout macros take the keys as they are placed on the board
+//! and lay them out as the 4x12 matrix. Since the number of keys and the
+//! dimensions of every layer are part of their types, a missing or extra key
+//! is a compile error rather than a shifted row.
+
+/// Declares a keymap as a static array of layers, plus a `u8` constant for
+/// each named layer holding its index.
+///
+/// Fails to compile if a layer doesn't have the given dimensions, or if
+/// there are more layers than `MAX_LAYERS`.
+#[macro_export]
+macro_rules! keymap {
+    (
+        $(#[$attr:meta])*
+        $vis:vis static $keymap:ident: [[$keycode:ty; $cols:expr]; $rows:expr] = {
+            $($name:ident => $layer:expr),+ $(,)?
+        };
+    ) => {
+        $crate::keymap!(@indices $vis, 0; $($name),+);
+
+        $(#[$attr])*
+        $vis static $keymap: [[[$keycode; $cols]; $rows]; [$(stringify!($name)),+].len()] = [
+            $($layer),+
+        ];
+
+        const _: () = assert!(
+            [$(stringify!($name)),+].len() <= $crate::layer::MAX_LAYERS,
+            "too many layers",
+        );
+    };
+
+    (@indices $vis:vis, $index:expr; $name:ident $(, $rest:ident)*) => {
+        $vis const $name: u8 = $index;
+        $crate::keymap!(@indices $vis, $index + 1; $($rest),*);
+    };
+    (@indices $vis:vis, $index:expr;) => {};
+}
+
+/// A Planck layer with a key on every position of the 4x12 grid.
+#[macro_export]
+macro_rules! layout_planck_grid {
+    (
+        $k00:expr, $k01:expr, $k02:expr, $k03:expr, $k04:expr, $k05:expr, $k06:expr, $k07:expr, $k08:expr, $k09:expr, $k0a:expr, $k0b:expr,
+        $k10:expr, $k11:expr, $k12:expr, $k13:expr, $k14:expr, $k15:expr, $k16:expr, $k17:expr, $k18:expr, $k19:expr, $k1a:expr, $k1b:expr,
+        $k20:expr, $k21:expr, $k22:expr, $k23:expr, $k24:expr, $k25:expr, $k26:expr, $k27:expr, $k28:expr, $k29:expr, $k2a:expr, $k2b:expr,
+        $k30:expr, $k31:expr, $k32:expr, $k33:expr, $k34:expr, $k35:expr, $k36:expr, $k37:expr, $k38:expr, $k39:expr, $k3a:expr, $k3b:expr $(,)?
+    ) => {
+        [
+            [$k00, $k01, $k02, $k03, $k04, $k05, $k06, $k07, $k08, $k09, $k0a, $k0b],
+            [$k10, $k11, $k12, $k13, $k14, $k15, $k16, $k17, $k18, $k19, $k1a, $k1b],
+            [$k20, $k21, $k22, $k23, $k24, $k25, $k26, $k27, $k28, $k29, $k2a, $k2b],
+            [$k30, $k31, $k32, $k33, $k34, $k35, $k36, $k37, $k38, $k39, $k3a, $k3b],
+        ]
+    };
+}
+
+/// A Planck layer with a 2u key in the middle of the bottom row (QMK's
+/// `LAYOUT_planck_mit`).
+///
+/// The 2u key's switch can sit on either of the two positions it covers, so
+/// its keycode goes on both.
+#[macro_export]
+macro_rules! layout_planck_mit {
+    (
+        $k00:expr, $k01:expr, $k02:expr, $k03:expr, $k04:expr, $k05:expr, $k06:expr, $k07:expr, $k08:expr, $k09:expr, $k0a:expr, $k0b:expr,
+        $k10:expr, $k11:expr, $k12:expr, $k13:expr, $k14:expr, $k15:expr, $k16:expr, $k17:expr, $k18:expr, $k19:expr, $k1a:expr, $k1b:expr,
+        $k20:expr, $k21:expr, $k22:expr, $k23:expr, $k24:expr, $k25:expr, $k26:expr, $k27:expr, $k28:expr, $k29:expr, $k2a:expr, $k2b:expr,
+        $k30:expr, $k31:expr, $k32:expr, $k33:expr, $k34:expr, $k35:expr, $k37:expr, $k38:expr, $k39:expr, $k3a:expr, $k3b:expr $(,)?
+    ) => {
+        [
+            [$k00, $k01, $k02, $k03, $k04, $k05, $k06, $k07, $k08, $k09, $k0a, $k0b],
+            [$k10, $k11, $k12, $k13, $k14, $k15, $k16, $k17, $k18, $k19, $k1a, $k1b],
+            [$k20, $k21, $k22, $k23, $k24, $k25, $k26, $k27, $k28, $k29, $k2a, $k2b],
+            [$k30, $k31, $k32, $k33, $k34, $k35, $k35, $k37, $k38, $k39, $k3a, $k3b],
+        ]
+    };
+}
+
+/// A Planck layer with two 2u keys in the middle of the bottom row (QMK's
+/// `LAYOUT_planck_2x2u`).
+///
+/// As with `layout_planck_mit!`, each 2u key's keycode goes on both of the
+/// positions it covers.
+#[macro_export]
+macro_rules! layout_planck_2x2u {
+    (
+        $k00:expr, $k01:expr, $k02:expr, $k03:expr, $k04:expr, $k05:expr, $k06:expr, $k07:expr, $k08:expr, $k09:expr, $k0a:expr, $k0b:expr,
+        $k10:expr, $k11:expr, $k12:expr, $k13:expr, $k14:expr, $k15:expr, $k16:expr, $k17:expr, $k18:expr, $k19:expr, $k1a:expr, $k1b:expr,
+        $k20:expr, $k21:expr, $k22:expr, $k23:expr, $k24:expr, $k25:expr, $k26:expr, $k27:expr, $k28:expr, $k29:expr, $k2a:expr, $k2b:expr,
+        $k30:expr, $k31:expr, $k32:expr, $k33:expr, $k34:expr, $k36:expr, $k38:expr, $k39:expr, $k3a:expr, $k3b:expr $(,)?
+    ) => {
+        [
+            [$k00, $k01, $k02, $k03, $k04, $k05, $k06, $k07, $k08, $k09, $k0a, $k0b],
+            [$k10, $k11, $k12, $k13, $k14, $k15, $k16, $k17, $k18, $k19, $k1a, $k1b],
+            [$k20, $k21, $k22, $k23, $k24, $k25, $k26, $k27, $k28, $k29, $k2a, $k2b],
+            [$k30, $k31, $k32, $k33, $k34, $k34, $k36, $k36, $k38, $k39, $k3a, $k3b],
+        ]
+    };
+}
@@ -0,0 +1,24 @@
+//! Hardware-agnostic keyboard logic: matrix state goes in, HID reports come
+//! out.
+//!
+//! This crate doesn't touch any peripherals, so it builds for both the AVR
+//! target and the host, where it is tested.
+
+#![no_std]
+
+pub mod backlight;
+pub mod boot;
+pub mod debounce;
+pub mod extrakey;
+pub mod host_leds;
+pub mod keyboard;
+pub mod keycode;
+pub mod layer;
+pub mod layout;
+pub mod matrix;
+pub mod mousekey;
+pub mod nkro;
+pub mod queue;
+pub mod spsc;
+pub mod tap_dance;
+pub mod tap_hold;
@@ -0,0 +1,85 @@
+use crate::keycode::KeyAction;
+
+/// A change in the state of a single key in the matrix.
+#[derive(Clone, Copy, PartialEq)]
+pub struct KeyEvent {
+    pub row: u8,
+    pub col: u8,
+    pub action: KeyAction,
+}
+
+impl KeyEvent {
+    pub const fn pressed(row: u8, col: u8) -> Self {
+        Self {
+            row,
+            col,
+            action: KeyAction::Pressed,
+        }
+    }
+
+    pub const fn released(row: u8, col: u8) -> Self {
+        Self {
+            row,
+            col,
+            action: KeyAction::Released,
+        }
+    }
+}
+
+/// Tracks the pressed keys of a matrix between scans.
+///
+/// Each row is a bitmask of pressed columns, so there can be at most 16
+/// columns.
+pub struct Matrix<const ROWS: usize> {
+    pressed: [u16; ROWS],
+}
+
+impl<const ROWS: usize> Matrix<ROWS> {
+    pub const fn new() -> Self {
+        Self { pressed: [0; ROWS] }
+    }
+
+    pub fn is_pressed(&self, row: u8, col: u8) -> bool {
+        (self.pressed[row as usize] & (1 << col)) != 0
+    }
+
+    /// Updates the matrix with the result of a new scan, calling `handler`
+    /// for each key that changed state.
+    pub fn update(&mut self, scan: &[u16; ROWS], mut handler: impl FnMut(KeyEvent)) {
+        self.try_update(scan, |event| {
+            handler(event);
+            true
+        });
+    }
+
+    /// Like `update`, but `handler` may refuse an event (e.g. because the
+    /// queue it goes to is full) by returning `false`.
+    ///
+    /// The refused key and all keys after it keep their previous state, so
+    /// their events are reported again, in order, by a later update.
+    pub fn try_update(&mut self, scan: &[u16; ROWS], mut handler: impl FnMut(KeyEvent) -> bool) {
+        for (i, (prev, &next)) in self.pressed.iter_mut().zip(scan).enumerate() {
+            let changed = *prev ^ next;
+            for j in 0..16 {
+                let bit = 1 << j;
+                if (changed & bit) != 0 {
+                    let event = if (next & bit) != 0 {
+                        KeyEvent::pressed(i as u8, j)
+                    } else {
+                        KeyEvent::released(i as u8, j)
+                    };
+                    if !handler(event) {
+                        return;
+                    }
+                    *prev ^= bit;
+                }
+            }
+        }
+    }
+}
+
+impl<const ROWS: usize> Default for Matrix<ROWS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
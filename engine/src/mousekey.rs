@@ -0,0 +1,238 @@
+//! Moving the pointer and scrolling with keys.
+//!
+//! Holding a movement key moves once right away, then again after `delay`
+//! and every `interval` after that, speeding up along the acceleration curve
+//! until full speed is reached (like QMK's default mouse keys). Times are
+//! given in milliseconds from a wrapping `u16` clock.
+
+/// The report sent to the host: buttons 1 to 8, a pointer move, and a wheel
+/// and pan (horizontal wheel) move.
+pub use usbd_hid::descriptor::MouseReport;
+
+use crate::keycode::MouseKeycode;
+
+/// How the speed ramps up from `delta` to full speed.
+#[derive(Clone, Copy, PartialEq)]
+pub enum Curve {
+    /// Speed grows steadily with every repeat.
+    Linear,
+    /// Speed grows slowly at first, for finer control of short movements.
+    Quadratic,
+}
+
+/// The speed of the pointer (or wheel) while a key is held.
+#[derive(Clone, Copy)]
+pub struct Acceleration {
+    /// Time between the first and second move, in milliseconds.
+    pub delay: u16,
+    /// Time between the following moves, in milliseconds.
+    pub interval: u16,
+    /// Distance of the first move.
+    pub delta: u8,
+    /// Distance of a move at full speed, as a multiple of `delta`.
+    pub max_speed: u8,
+    /// Number of repeats until full speed is reached.
+    pub time_to_max: u8,
+    pub curve: Curve,
+}
+
+impl Acceleration {
+    /// QMK's default pointer acceleration.
+    pub const CURSOR: Self = Self {
+        delay: 10,
+        interval: 20,
+        delta: 8,
+        max_speed: 10,
+        time_to_max: 30,
+        curve: Curve::Linear,
+    };
+
+    /// QMK's default wheel acceleration.
+    pub const WHEEL: Self = Self {
+        delay: 10,
+        interval: 80,
+        delta: 1,
+        max_speed: 8,
+        time_to_max: 40,
+        curve: Curve::Linear,
+    };
+
+    /// The distance of a move, given how many times the move has repeated
+    /// and which accelerator key is held (0-2), if any.
+    pub fn distance(&self, repeat: u8, accel: Option<u8>) -> u8 {
+        let delta = u32::from(self.delta);
+        let max = delta * u32::from(self.max_speed);
+        let (repeat, time_to_max) = (u32::from(repeat), u32::from(self.time_to_max));
+        let distance = match accel {
+            Some(0) => max / 4,
+            Some(1) => max / 2,
+            Some(_) => max,
+            None if repeat >= time_to_max => max,
+            None => match self.curve {
+                Curve::Linear => max * repeat / time_to_max,
+                Curve::Quadratic => max * repeat * repeat / (time_to_max * time_to_max),
+            },
+        };
+        distance.max(delta).clamp(1, i8::MAX as u32) as u8
+    }
+}
+
+// A movement (pointer or wheel) in progress.
+#[derive(Clone, Copy)]
+struct Motion {
+    repeat: u8,
+    last: u16,
+}
+
+impl Motion {
+    fn is_due(&self, acceleration: &Acceleration, now: u16) -> bool {
+        let wait = if self.repeat == 0 {
+            acceleration.delay
+        } else {
+            acceleration.interval
+        };
+        now.wrapping_sub(self.last) >= wait
+    }
+}
+
+const fn bit(keycode: MouseKeycode) -> u32 {
+    1 << keycode as u8
+}
+
+const CURSOR_KEYS: u32 = bit(MouseKeycode::Up)
+    | bit(MouseKeycode::Down)
+    | bit(MouseKeycode::Left)
+    | bit(MouseKeycode::Right);
+const WHEEL_KEYS: u32 = bit(MouseKeycode::WheelUp)
+    | bit(MouseKeycode::WheelDown)
+    | bit(MouseKeycode::WheelLeft)
+    | bit(MouseKeycode::WheelRight);
+
+/// The state of the mouse keys, turning key presses into mouse reports.
+pub struct MouseKeys {
+    cursor: Acceleration,
+    wheel: Acceleration,
+    // Held keys, one bit per `MouseKeycode`.
+    held: u32,
+    cursor_motion: Motion,
+    wheel_motion: Motion,
+}
+
+impl MouseKeys {
+    pub const fn new(cursor: Acceleration, wheel: Acceleration) -> Self {
+        Self {
+            cursor,
+            wheel,
+            held: 0,
+            cursor_motion: Motion { repeat: 0, last: 0 },
+            wheel_motion: Motion { repeat: 0, last: 0 },
+        }
+    }
+
+    /// Presses a key, returning the report to send for it, if any.
+    ///
+    /// Movement keys move once right away.
+    pub fn press(&mut self, keycode: MouseKeycode, now: u16) -> Option<MouseReport> {
+        let was_held = self.held;
+        self.held |= bit(keycode);
+        if (was_held & CURSOR_KEYS) == 0 && (self.held & CURSOR_KEYS) != 0 {
+            self.cursor_motion = Motion {
+                repeat: 0,
+                last: now,
+            };
+        }
+        if (was_held & WHEEL_KEYS) == 0 && (self.held & WHEEL_KEYS) != 0 {
+            self.wheel_motion = Motion {
+                repeat: 0,
+                last: now,
+            };
+        }
+        let key = bit(keycode);
+        match keycode {
+            MouseKeycode::Accel0 | MouseKeycode::Accel1 | MouseKeycode::Accel2 => None,
+            _ => Some(self.report((key & CURSOR_KEYS) != 0, (key & WHEEL_KEYS) != 0)),
+        }
+    }
+
+    /// Releases a key, returning the report to send for it, if any.
+    pub fn release(&mut self, keycode: MouseKeycode) -> Option<MouseReport> {
+        self.held &= !bit(keycode);
+        match keycode {
+            MouseKeycode::Button1
+            | MouseKeycode::Button2
+            | MouseKeycode::Button3
+            | MouseKeycode::Button4
+            | MouseKeycode::Button5
+            | MouseKeycode::Button6
+            | MouseKeycode::Button7
+            | MouseKeycode::Button8 => Some(self.report(false, false)),
+            _ => None,
+        }
+    }
+
+    /// Repeats the held movements, returning a report if any of them is due.
+    pub fn tick(&mut self, now: u16) -> Option<MouseReport> {
+        let cursor = (self.held & CURSOR_KEYS) != 0 && self.cursor_motion.is_due(&self.cursor, now);
+        let wheel = (self.held & WHEEL_KEYS) != 0 && self.wheel_motion.is_due(&self.wheel, now);
+        for (moved, motion) in [
+            (cursor, &mut self.cursor_motion),
+            (wheel, &mut self.wheel_motion),
+        ] {
+            if moved {
+                motion.repeat = motion.repeat.saturating_add(1);
+                motion.last = now;
+            }
+        }
+        if cursor || wheel {
+            Some(self.report(cursor, wheel))
+        } else {
+            None
+        }
+    }
+
+    fn accel(&self) -> Option<u8> {
+        [
+            MouseKeycode::Accel0,
+            MouseKeycode::Accel1,
+            MouseKeycode::Accel2,
+        ]
+        .iter()
+        .position(|&keycode| (self.held & bit(keycode)) != 0)
+        .map(|i| i as u8)
+    }
+
+    /// -1, 0 or 1, depending on which of two opposite keys are held.
+    fn axis(&self, negative: MouseKeycode, positive: MouseKeycode) -> i8 {
+        ((self.held & bit(positive)) != 0) as i8 - ((self.held & bit(negative)) != 0) as i8
+    }
+
+    /// The current buttons, with a move of the pointer and/or the wheel.
+    fn report(&self, cursor: bool, wheel: bool) -> MouseReport {
+        let mut report = MouseReport {
+            buttons: (self.held >> MouseKeycode::Button1 as u8) as u8,
+            x: 0,
+            y: 0,
+            wheel: 0,
+            pan: 0,
+        };
+        if cursor {
+            let x = self.axis(MouseKeycode::Left, MouseKeycode::Right);
+            let y = self.axis(MouseKeycode::Up, MouseKeycode::Down);
+            let mut distance = self
+                .cursor
+                .distance(self.cursor_motion.repeat, self.accel());
+            if x != 0 && y != 0 {
+                // Keep the same speed diagonally (1/sqrt(2) ~= 181/256).
+                distance = ((u16::from(distance) * 181) >> 8).max(1) as u8;
+            }
+            report.x = x * distance as i8;
+            report.y = y * distance as i8;
+        }
+        if wheel {
+            let distance = self.wheel.distance(self.wheel_motion.repeat, self.accel()) as i8;
+            report.wheel = self.axis(MouseKeycode::WheelDown, MouseKeycode::WheelUp) * distance;
+            report.pan = self.axis(MouseKeycode::WheelLeft, MouseKeycode::WheelRight) * distance;
+        }
+        report
+    }
+}
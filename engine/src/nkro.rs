@@ -6,16 +6,25 @@ use usbd_hid::descriptor::generator_prelude::*;
     (collection = APPLICATION, usage_page = GENERIC_DESKTOP, usage = KEYBOARD) = {
         (usage_page = KEYBOARD, usage_min = 0x00, usage_max = 0xe7) = {
             #[packed_bits 232] #[item_settings data,variable,absolute] keys=input;
-        }
+        };
+        (usage_page = LEDS, usage_min = 0x01, usage_max = 0x05) = {
+            #[packed_bits 5] #[item_settings data,variable,absolute] leds=output;
+        };
     }
 )]
 pub struct NkroKeyboardReport {
     keys: [u8; 29],
+    // Only declares the output report (see `host_leds`); never sent.
+    leds: u8,
 }
 
 impl NkroKeyboardReport {
+    #[allow(clippy::new_without_default)]
     pub const fn new() -> Self {
-        Self { keys: [0; 29] }
+        Self {
+            keys: [0; 29],
+            leds: 0,
+        }
     }
 
     pub fn press(&mut self, index: u8) {
@@ -26,8 +35,11 @@ impl NkroKeyboardReport {
         self.keys[index as usize / 8] &= !(1 << (index % 8));
     }
 
-    pub fn clear_all_but_mods(&mut self) {
-        // excluding the last byte (codes 0xe0..=0xe7)
-        self.keys[..28].fill(0);
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.keys
+    }
+
+    pub fn is_pressed(&self, index: u8) -> bool {
+        (self.keys[index as usize / 8] & (1 << (index % 8))) != 0
     }
 }
@@ -0,0 +1,103 @@
+use core::mem::MaybeUninit;
+
+/// A fixed-capacity FIFO queue.
+pub struct Queue<T: Copy, const N: usize> {
+    buf: [MaybeUninit<T>; N],
+    head: usize,
+    len: usize,
+}
+
+impl<T: Copy, const N: usize> Queue<T, N> {
+    pub const fn new() -> Self {
+        Self {
+            buf: [const { MaybeUninit::uninit() }; N],
+            head: 0,
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.len == N
+    }
+
+    /// Appends a value to the back of the queue, or gives it back if the
+    /// queue is full.
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(value);
+        }
+        self.buf[(self.head + self.len) % N].write(value);
+        self.len += 1;
+        Ok(())
+    }
+
+    /// Removes the value at the front of the queue.
+    pub fn pop(&mut self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        // Safety: every slot between head and head + len has been written.
+        let value = unsafe { self.buf[self.head].assume_init() };
+        self.head = (self.head + 1) % N;
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// The value at `index`, counting from the front.
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.len {
+            return None;
+        }
+        // Safety: see `pop`.
+        Some(unsafe { self.buf[(self.head + index) % N].assume_init_ref() })
+    }
+
+    /// The value at `index`, counting from the front.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.len {
+            return None;
+        }
+        // Safety: see `pop`.
+        Some(unsafe { self.buf[(self.head + index) % N].assume_init_mut() })
+    }
+
+    /// Removes the value at `index`, counting from the front. The values
+    /// behind it move up.
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        let value = *self.get(index)?;
+        for i in index..self.len - 1 {
+            self.buf[(self.head + i) % N] = self.buf[(self.head + i + 1) % N];
+        }
+        self.len -= 1;
+        Some(value)
+    }
+
+    /// The most recently pushed value.
+    pub fn back_mut(&mut self) -> Option<&mut T> {
+        if self.is_empty() {
+            return None;
+        }
+        // Safety: see `pop`.
+        Some(unsafe { self.buf[(self.head + self.len - 1) % N].assume_init_mut() })
+    }
+
+    /// Iterates over the queue from front to back.
+    pub fn iter(&self) -> impl Iterator<Item = &T> {
+        // Safety: see `pop`.
+        (0..self.len).map(move |i| unsafe { self.buf[(self.head + i) % N].assume_init_ref() })
+    }
+}
+
+impl<T: Copy, const N: usize> Default for Queue<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
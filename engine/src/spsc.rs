@@ -0,0 +1,128 @@
+//! A lock-free queue for passing values from one context to another, e.g.
+//! from an interrupt handler to the main loop.
+//!
+//! Only loads and stores of the indices need to be atomic, so this works on
+//! targets without compare-and-swap (like AVR).
+
+use core::{
+    cell::UnsafeCell,
+    mem::MaybeUninit,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// A bounded single-producer, single-consumer FIFO queue.
+///
+/// The queue is used through the halves returned by `split`. `N` must be a
+/// power of two, and is the number of values the queue can hold.
+pub struct SpscQueue<T: Copy, const N: usize> {
+    buf: UnsafeCell<[MaybeUninit<T>; N]>,
+    // Number of values popped, wrapping. Only written by the consumer.
+    head: AtomicUsize,
+    // Number of values pushed, wrapping. Only written by the producer.
+    tail: AtomicUsize,
+}
+
+// Safety: the producer and the consumer never access the same slot at the
+// same time; the indices hand slots over between them.
+unsafe impl<T: Copy + Send, const N: usize> Sync for SpscQueue<T, N> {}
+
+impl<T: Copy, const N: usize> SpscQueue<T, N> {
+    pub const fn new() -> Self {
+        // The wrapping indices stay consistent modulo N only if N divides the
+        // range of usize.
+        assert!(N.is_power_of_two());
+        Self {
+            buf: UnsafeCell::new([const { MaybeUninit::uninit() }; N]),
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        }
+    }
+
+    /// Splits the queue into the halves used by the producing and the
+    /// consuming context.
+    pub fn split(&mut self) -> (Producer<'_, T, N>, Consumer<'_, T, N>) {
+        (Producer { queue: self }, Consumer { queue: self })
+    }
+
+    fn len(&self) -> usize {
+        let tail = self.tail.load(Ordering::Acquire);
+        let head = self.head.load(Ordering::Acquire);
+        tail.wrapping_sub(head)
+    }
+
+    fn slot(&self, index: usize) -> *mut MaybeUninit<T> {
+        // Safety: in bounds.
+        unsafe { (self.buf.get() as *mut MaybeUninit<T>).add(index % N) }
+    }
+}
+
+impl<T: Copy, const N: usize> Default for SpscQueue<T, N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The pushing half of an `SpscQueue`.
+pub struct Producer<'a, T: Copy, const N: usize> {
+    queue: &'a SpscQueue<T, N>,
+}
+
+// Safety: see `SpscQueue`.
+unsafe impl<T: Copy + Send, const N: usize> Send for Producer<'_, T, N> {}
+
+impl<T: Copy, const N: usize> Producer<'_, T, N> {
+    pub fn is_full(&self) -> bool {
+        self.queue.len() == N
+    }
+
+    /// Appends a value to the back of the queue, or gives it back if the
+    /// queue is full.
+    pub fn push(&mut self, value: T) -> Result<(), T> {
+        if self.is_full() {
+            return Err(value);
+        }
+        let tail = self.queue.tail.load(Ordering::Relaxed);
+        // Safety: the slot isn't visible to the consumer until `tail` moves
+        // past it.
+        unsafe { (*self.queue.slot(tail)).write(value) };
+        self.queue.tail.store(tail.wrapping_add(1), Ordering::Release);
+        Ok(())
+    }
+}
+
+/// The popping half of an `SpscQueue`.
+pub struct Consumer<'a, T: Copy, const N: usize> {
+    queue: &'a SpscQueue<T, N>,
+}
+
+// Safety: see `SpscQueue`.
+unsafe impl<T: Copy + Send, const N: usize> Send for Consumer<'_, T, N> {}
+
+impl<T: Copy, const N: usize> Consumer<'_, T, N> {
+    pub fn len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The value at the front of the queue, without removing it.
+    pub fn peek(&self) -> Option<T> {
+        if self.is_empty() {
+            return None;
+        }
+        let head = self.queue.head.load(Ordering::Relaxed);
+        // Safety: the producer has written the slot before moving `tail`
+        // past it, and doesn't touch it until `head` moves past it.
+        Some(unsafe { (*self.queue.slot(head)).assume_init() })
+    }
+
+    /// Removes the value at the front of the queue.
+    pub fn pop(&mut self) -> Option<T> {
+        let value = self.peek()?;
+        let head = self.queue.head.load(Ordering::Relaxed);
+        self.queue.head.store(head.wrapping_add(1), Ordering::Release);
+        Some(value)
+    }
+}
@@ -0,0 +1,171 @@
+//! Tap dance: a key that does different things depending on how many times
+//! it is tapped in a row, and whether it is held.
+//!
+//! A dance goes on for as long as the key is tapped again within the tap
+//! dance term. It ends when the term passes without another tap, when
+//! another key is pressed, or as soon as more taps can't change the outcome.
+
+use crate::keycode::{qmk::KC_NO, Keycode};
+
+/// The actions of a tap dance key.
+///
+/// `KC_NO` leaves an action out, so the dance doesn't wait for a tap or hold
+/// that would do nothing. Actions can be any keycode except tap-hold keys and
+/// tap dances, which do nothing.
+#[derive(Clone, Copy)]
+pub struct TapDance {
+    /// Tapped once, or held if there is no hold action.
+    pub tap: Keycode,
+    /// Held down on the first press until the term passes.
+    pub hold: Keycode,
+    pub double_tap: Keycode,
+    pub triple_tap: Keycode,
+}
+
+impl TapDance {
+    /// Sends `tap` when tapped once and `double_tap` when tapped twice, like
+    /// QMK's `ACTION_TAP_DANCE_DOUBLE`.
+    pub const fn double(tap: Keycode, double_tap: Keycode) -> Self {
+        Self {
+            tap,
+            hold: KC_NO,
+            double_tap,
+            triple_tap: KC_NO,
+        }
+    }
+
+    /// Sends `tap` when tapped and `hold` when held.
+    pub const fn tap_hold(tap: Keycode, hold: Keycode) -> Self {
+        Self {
+            tap,
+            hold,
+            double_tap: KC_NO,
+            triple_tap: KC_NO,
+        }
+    }
+
+    /// The action for the outcome of a dance.
+    pub fn action(&self, outcome: Outcome) -> Keycode {
+        match outcome {
+            Outcome::Hold => self.hold,
+            Outcome::Taps(1) => self.tap,
+            Outcome::Taps(2) => self.double_tap,
+            Outcome::Taps(3) => self.triple_tap,
+            Outcome::Taps(_) => KC_NO,
+        }
+    }
+
+    /// The most taps that do something.
+    fn max_taps(&self) -> u8 {
+        if self.triple_tap != KC_NO {
+            3
+        } else if self.double_tap != KC_NO {
+            2
+        } else {
+            1
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Outcome {
+    /// Tapped this many times. The last tap may still be held down.
+    Taps(u8),
+    /// Held down on the first press.
+    Hold,
+}
+
+/// How a dance ended.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Decision {
+    pub outcome: Outcome,
+    /// Whether the key is still held down. If so, the action should stay
+    /// pressed until the key is released; otherwise it is tapped.
+    pub held: bool,
+}
+
+/// A tap dance in progress.
+#[derive(Clone, Copy)]
+pub struct Dance {
+    pub row: u8,
+    pub col: u8,
+    /// The index of the tap dance in `Config::tap_dances`.
+    pub index: u8,
+    taps: u8,
+    pressed: bool,
+    // When the key was last pressed or released.
+    since: u16,
+}
+
+impl Dance {
+    /// Starts a dance on the first press of a tap dance key.
+    pub fn start(
+        row: u8,
+        col: u8,
+        index: u8,
+        tap_dance: &TapDance,
+        now: u16,
+    ) -> (Self, Option<Decision>) {
+        let mut dance = Self {
+            row,
+            col,
+            index,
+            taps: 0,
+            pressed: false,
+            since: now,
+        };
+        let decision = dance.press(tap_dance, now);
+        (dance, decision)
+    }
+
+    /// The key was pressed again.
+    pub fn press(&mut self, tap_dance: &TapDance, now: u16) -> Option<Decision> {
+        self.taps = self.taps.saturating_add(1);
+        self.pressed = true;
+        self.since = now;
+        // The first press waits to tell a tap from a hold.
+        let waits_for_hold = self.taps == 1 && tap_dance.hold != KC_NO;
+        if self.taps >= tap_dance.max_taps() && !waits_for_hold {
+            return Some(self.decision(Outcome::Taps(self.taps)));
+        }
+        None
+    }
+
+    /// The key was released.
+    pub fn release(&mut self, tap_dance: &TapDance, now: u16) -> Option<Decision> {
+        self.pressed = false;
+        self.since = now;
+        if self.taps >= tap_dance.max_taps() {
+            return Some(self.decision(Outcome::Taps(self.taps)));
+        }
+        None
+    }
+
+    /// Another key was pressed, which ends the dance with the taps so far.
+    pub fn interrupt(&self) -> Decision {
+        self.decision(Outcome::Taps(self.taps))
+    }
+
+    /// Ends the dance if the term has passed since the key was last pressed
+    /// or released.
+    ///
+    /// Should be called before every press or release, so that a late one
+    /// starts a new dance instead of adding to this one.
+    pub fn tick(&self, tap_dance: &TapDance, term: u16, now: u16) -> Option<Decision> {
+        if now.wrapping_sub(self.since) < term {
+            return None;
+        }
+        if self.pressed && self.taps == 1 && tap_dance.hold != KC_NO {
+            Some(self.decision(Outcome::Hold))
+        } else {
+            Some(self.decision(Outcome::Taps(self.taps)))
+        }
+    }
+
+    fn decision(&self, outcome: Outcome) -> Decision {
+        Decision {
+            outcome,
+            held: self.pressed,
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Deciding whether a dual-role key was tapped or held.
+//!
+//! While the decision is pending, the events that follow the key press are
+//! deferred, so they can be processed after the dual-role key has taken
+//! effect in whichever role it was decided to have.
+
+use crate::{keyboard::Config, matrix::KeyEvent};
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Decision {
+    Tap,
+    Hold,
+}
+
+/// A dual-role key that was pressed, but whose role hasn't been decided yet.
+#[derive(Clone, Copy)]
+pub struct Pending {
+    pub row: u8,
+    pub col: u8,
+    pub since: u16,
+}
+
+impl Pending {
+    /// Decides the role of the pending key, given the events that were
+    /// deferred since it was pressed, in order, and the current time.
+    ///
+    /// Returns `None` if there isn't enough information yet.
+    pub fn decide<'a, const ROWS: usize>(
+        &self,
+        config: &Config,
+        deferred: impl IntoIterator<Item = &'a (KeyEvent, u16)>,
+        now: u16,
+    ) -> Option<Decision> {
+        // Keys that were pressed after the pending key.
+        let mut pressed_after = [0u16; ROWS];
+        for &(event, time) in deferred {
+            if time.wrapping_sub(self.since) >= config.tapping_term {
+                return Some(Decision::Hold);
+            }
+            let bit = 1 << event.col;
+            if (event.row, event.col) == (self.row, self.col) {
+                // Released within the tapping term.
+                return Some(Decision::Tap);
+            } else if event.action.is_pressed() {
+                if config.hold_on_other_key_press {
+                    return Some(Decision::Hold);
+                }
+                pressed_after[event.row as usize] |= bit;
+            } else if config.permissive_hold && (pressed_after[event.row as usize] & bit) != 0 {
+                return Some(Decision::Hold);
+            }
+        }
+        if now.wrapping_sub(self.since) >= config.tapping_term {
+            Some(Decision::Hold)
+        } else {
+            None
+        }
+    }
+}
@@ -0,0 +1,86 @@
+use engine::{backlight::Backlight, keycode::SystemKeycode};
+
+fn backlight() -> Backlight {
+    Backlight::new(3, 1000)
+}
+
+#[test]
+fn starts_off() {
+    let bl = backlight();
+    assert_eq!(bl.level(), 0);
+    assert_eq!(bl.duty(0), 0);
+}
+
+#[test]
+fn increase_and_decrease() {
+    let mut bl = backlight();
+    bl.increase();
+    assert_eq!(bl.level(), 1);
+    assert_eq!(bl.duty(0), 85);
+    bl.increase();
+    bl.increase();
+    bl.increase();
+    assert_eq!(bl.level(), 3);
+    assert_eq!(bl.duty(0), 255);
+    bl.decrease();
+    assert_eq!(bl.level(), 2);
+    bl.decrease();
+    bl.decrease();
+    assert_eq!(bl.level(), 0);
+    bl.decrease();
+    assert_eq!(bl.level(), 0);
+}
+
+#[test]
+fn step_wraps_to_off() {
+    let mut bl = backlight();
+    let mut levels = [0; 5];
+    for level in &mut levels {
+        bl.step();
+        *level = bl.level();
+    }
+    assert_eq!(levels, [1, 2, 3, 0, 1]);
+}
+
+#[test]
+fn toggle_keeps_level() {
+    let mut bl = backlight();
+    bl.toggle();
+    assert_eq!(bl.level(), 3);
+    bl.decrease();
+    bl.toggle();
+    assert_eq!(bl.level(), 0);
+    bl.toggle();
+    assert_eq!(bl.level(), 2);
+}
+
+#[test]
+fn handles_keys() {
+    let mut bl = backlight();
+    bl.handle(SystemKeycode::BacklightUp);
+    bl.handle(SystemKeycode::BacklightUp);
+    bl.handle(SystemKeycode::BacklightDown);
+    assert_eq!(bl.level(), 1);
+    bl.handle(SystemKeycode::BacklightStep);
+    assert_eq!(bl.level(), 2);
+    bl.handle(SystemKeycode::BacklightToggle);
+    assert_eq!(bl.level(), 0);
+    bl.handle(SystemKeycode::Reset);
+    assert_eq!(bl.level(), 0);
+    bl.handle(SystemKeycode::BacklightBreathing);
+    assert!(bl.is_breathing());
+}
+
+#[test]
+fn breathing() {
+    let mut bl = backlight();
+    bl.toggle();
+    bl.handle(SystemKeycode::BacklightBreathing);
+    assert_eq!(bl.duty(0), 0);
+    assert_eq!(bl.duty(250), 127);
+    assert_eq!(bl.duty(500), 255);
+    assert_eq!(bl.duty(750), 127);
+    assert_eq!(bl.duty(1000), 0);
+    bl.toggle();
+    assert_eq!(bl.duty(500), 0);
+}
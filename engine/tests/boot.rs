@@ -0,0 +1,67 @@
+use engine::{boot::BootKeyboardReport, keycode::HidKeycode, nkro::NkroKeyboardReport};
+
+fn report(keys: &[HidKeycode]) -> NkroKeyboardReport {
+    let mut report = NkroKeyboardReport::new();
+    for &key in keys {
+        report.press(key as u8);
+    }
+    report
+}
+
+#[test]
+fn empty() {
+    let boot = BootKeyboardReport::from_nkro(&NkroKeyboardReport::new());
+    assert_eq!(boot.as_bytes(), [0; 8]);
+}
+
+#[test]
+fn modifiers_and_keys() {
+    let boot = BootKeyboardReport::from_nkro(&report(&[
+        HidKeycode::LeftShift,
+        HidKeycode::RightGui,
+        HidKeycode::B,
+        HidKeycode::A,
+    ]));
+    assert_eq!(
+        boot.as_bytes(),
+        [
+            0x82,
+            0,
+            HidKeycode::A as u8,
+            HidKeycode::B as u8,
+            0,
+            0,
+            0,
+            0
+        ]
+    );
+}
+
+#[test]
+fn six_keys_fit() {
+    let keys = [
+        HidKeycode::A,
+        HidKeycode::B,
+        HidKeycode::C,
+        HidKeycode::D,
+        HidKeycode::E,
+        HidKeycode::F,
+    ];
+    let boot = BootKeyboardReport::from_nkro(&report(&keys));
+    assert_eq!(&boot.as_bytes()[2..], keys.map(|key| key as u8));
+}
+
+#[test]
+fn roll_over() {
+    let boot = BootKeyboardReport::from_nkro(&report(&[
+        HidKeycode::LeftControl,
+        HidKeycode::A,
+        HidKeycode::B,
+        HidKeycode::C,
+        HidKeycode::D,
+        HidKeycode::E,
+        HidKeycode::F,
+        HidKeycode::G,
+    ]));
+    assert_eq!(boot.as_bytes(), [0x01, 0, 1, 1, 1, 1, 1, 1]);
+}
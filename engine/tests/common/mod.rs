@@ -0,0 +1,57 @@
+//! Helpers for the tests that drive a `Keyboard` with key events.
+//!
+//! Events are on row 0, which is the only row of most test keymaps.
+
+// Each test file only uses some of them.
+#![allow(dead_code)]
+
+use engine::{
+    keyboard::{Config, Keyboard, Keymap},
+    keycode::HidKeycode,
+    matrix::KeyEvent,
+};
+
+/// A keyboard with the default config, changed by `configure`.
+pub fn keyboard<const ROWS: usize, const COLS: usize>(
+    keymap: &'static Keymap<ROWS, COLS>,
+    configure: impl FnOnce(&mut Config),
+) -> Keyboard<ROWS, COLS> {
+    let mut config = Config::new();
+    configure(&mut config);
+    Keyboard::new(keymap, config)
+}
+
+pub fn press<const ROWS: usize, const COLS: usize>(
+    keyboard: &mut Keyboard<ROWS, COLS>,
+    col: u8,
+    now: u16,
+) {
+    keyboard.event(KeyEvent::pressed(0, col), now);
+}
+
+pub fn release<const ROWS: usize, const COLS: usize>(
+    keyboard: &mut Keyboard<ROWS, COLS>,
+    col: u8,
+    now: u16,
+) {
+    keyboard.event(KeyEvent::released(0, col), now);
+}
+
+/// Whether a key is pressed in the current report.
+pub fn is_pressed<const ROWS: usize, const COLS: usize>(
+    keyboard: &Keyboard<ROWS, COLS>,
+    key: HidKeycode,
+) -> bool {
+    keyboard.report().is_pressed(key as u8)
+}
+
+/// Takes the queued reports, as lists of pressed keycodes.
+pub fn reports<const ROWS: usize, const COLS: usize>(
+    keyboard: &mut Keyboard<ROWS, COLS>,
+) -> Vec<Vec<u8>> {
+    let mut reports = Vec::new();
+    while let Some(report) = keyboard.pop_report() {
+        reports.push((0..=0xe7).filter(|&k| report.is_pressed(k)).collect());
+    }
+    reports
+}
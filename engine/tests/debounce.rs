@@ -0,0 +1,103 @@
+use engine::debounce::{Algorithm, Debouncer};
+
+/// Replays a trace of a single switch sampled once per millisecond starting
+/// at `start`, where each character is the raw state ('1' = closed).
+/// Returns the times at which the debounced state changed, and to what.
+fn replay(algorithm: Algorithm, window: u16, start: u16, trace: &str) -> Vec<(u16, bool)> {
+    let mut debouncer: Debouncer<1, 1> = Debouncer::new(algorithm, window);
+    let mut prev = false;
+    let mut edges = Vec::new();
+    for (t, c) in trace.chars().enumerate() {
+        let now = start.wrapping_add(t as u16);
+        let raw = [(c == '1') as u16];
+        let state = debouncer.update(&raw, now)[0] != 0;
+        if state != prev {
+            edges.push((t as u16, state));
+            prev = state;
+        }
+    }
+    edges
+}
+
+const BOUNCY: &str = "0001010111111111010100000000";
+const SPIKE: &str = "0001000000000";
+
+#[test]
+fn eager_per_key() {
+    assert_eq!(
+        replay(Algorithm::EagerPerKey, 5, 0, BOUNCY),
+        [(3, true), (16, false)]
+    );
+}
+
+#[test]
+fn eager_per_key_passes_spikes() {
+    assert_eq!(
+        replay(Algorithm::EagerPerKey, 5, 0, SPIKE),
+        [(3, true), (8, false)]
+    );
+}
+
+#[test]
+fn defer_per_key() {
+    assert_eq!(
+        replay(Algorithm::DeferPerKey, 5, 0, BOUNCY),
+        [(12, true), (25, false)]
+    );
+}
+
+#[test]
+fn defer_per_key_rejects_spikes() {
+    assert_eq!(replay(Algorithm::DeferPerKey, 5, 0, SPIKE), []);
+}
+
+#[test]
+fn eager_per_row() {
+    assert_eq!(
+        replay(Algorithm::EagerPerRow, 5, 0, BOUNCY),
+        [(3, true), (16, false)]
+    );
+}
+
+#[test]
+fn eager_per_row_locks_neighbours() {
+    let mut debouncer: Debouncer<2, 2> = Debouncer::new(Algorithm::EagerPerRow, 5);
+    assert_eq!(debouncer.update(&[0b01, 0b00], 0), &[0b01, 0b00]);
+    // The other key in the locked row has to wait for the window to expire,
+    // but the other row is unaffected.
+    assert_eq!(debouncer.update(&[0b11, 0b01], 2), &[0b01, 0b01]);
+    assert_eq!(debouncer.update(&[0b11, 0b01], 4), &[0b01, 0b01]);
+    assert_eq!(debouncer.update(&[0b11, 0b01], 5), &[0b11, 0b01]);
+}
+
+#[test]
+fn per_key_is_independent() {
+    let mut debouncer: Debouncer<1, 2> = Debouncer::new(Algorithm::EagerPerKey, 5);
+    assert_eq!(debouncer.update(&[0b01], 0), &[0b01]);
+    assert_eq!(debouncer.update(&[0b10], 1), &[0b11]);
+    assert_eq!(debouncer.update(&[0b10], 5), &[0b10]);
+}
+
+#[test]
+fn zero_window() {
+    for algorithm in [
+        Algorithm::EagerPerKey,
+        Algorithm::DeferPerKey,
+        Algorithm::EagerPerRow,
+    ] {
+        assert_eq!(replay(algorithm, 0, 0, "0110"), [(1, true), (3, false)]);
+    }
+}
+
+#[test]
+fn clock_wrap() {
+    let start = u16::MAX - 5;
+    assert_eq!(
+        replay(Algorithm::DeferPerKey, 5, start, BOUNCY),
+        [(12, true), (25, false)]
+    );
+    assert_eq!(
+        replay(Algorithm::EagerPerKey, 5, start, BOUNCY),
+        [(3, true), (16, false)]
+    );
+}
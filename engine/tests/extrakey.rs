@@ -0,0 +1,115 @@
+use engine::{
+    extrakey::{ExtraKeyReport, REPORT_ID_CONSUMER, REPORT_ID_SYSTEM},
+    keyboard::Keyboard,
+    keycode::{qmk::*, ConsumerKeycode, Keycode, SystemControlKeycode},
+    matrix::KeyEvent,
+};
+
+mod common;
+
+use common::keyboard;
+
+#[rustfmt::skip]
+static KEYMAP: [[[Keycode; 4]; 1]; 1] = [
+    [[KC_MPLY, KC_VOLU, KC_A   , KC_SLEP]],
+];
+
+fn extra_reports(kb: &mut Keyboard<1, 4>) -> Vec<[u8; 3]> {
+    std::iter::from_fn(|| kb.pop_extra_report())
+        .map(|report| report.to_bytes())
+        .collect()
+}
+
+fn consumer(keycode: ConsumerKeycode) -> [u8; 3] {
+    let mut report = ExtraKeyReport::consumer();
+    report.press(keycode as u16);
+    report.to_bytes()
+}
+
+#[test]
+fn report_bytes() {
+    let mut report = ExtraKeyReport::system();
+    assert_eq!(report.to_bytes(), [REPORT_ID_SYSTEM, 0, 0]);
+    report.press(SystemControlKeycode::Sleep as u16);
+    assert_eq!(report.to_bytes(), [REPORT_ID_SYSTEM, 0x82, 0x00]);
+    assert_eq!(
+        consumer(ConsumerKeycode::LocalMachineBrowser),
+        [REPORT_ID_CONSUMER, 0x94, 0x01]
+    );
+}
+
+#[test]
+fn consumer_press_and_release() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    kb.event(KeyEvent::pressed(0, 0), 0);
+    kb.event(KeyEvent::released(0, 0), 0);
+    assert_eq!(
+        extra_reports(&mut kb),
+        [
+            consumer(ConsumerKeycode::PlayPause),
+            [REPORT_ID_CONSUMER, 0, 0]
+        ]
+    );
+    // The keyboard report isn't touched.
+    assert!(kb.pop_report().is_none());
+}
+
+#[test]
+fn newest_consumer_key_wins() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    kb.event(KeyEvent::pressed(0, 0), 0);
+    kb.event(KeyEvent::pressed(0, 1), 0);
+    // Releasing the replaced key doesn't release the pressed one.
+    kb.event(KeyEvent::released(0, 0), 0);
+    kb.event(KeyEvent::released(0, 1), 0);
+    assert_eq!(
+        extra_reports(&mut kb),
+        [
+            consumer(ConsumerKeycode::PlayPause),
+            consumer(ConsumerKeycode::VolumeIncrement),
+            [REPORT_ID_CONSUMER, 0, 0]
+        ]
+    );
+}
+
+#[test]
+fn released_consumer_key_reports_the_one_still_held() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    kb.event(KeyEvent::pressed(0, 0), 0);
+    kb.event(KeyEvent::pressed(0, 1), 0);
+    kb.event(KeyEvent::released(0, 1), 0);
+    kb.event(KeyEvent::released(0, 0), 0);
+    assert_eq!(
+        extra_reports(&mut kb),
+        [
+            consumer(ConsumerKeycode::PlayPause),
+            consumer(ConsumerKeycode::VolumeIncrement),
+            consumer(ConsumerKeycode::PlayPause),
+            [REPORT_ID_CONSUMER, 0, 0]
+        ]
+    );
+}
+
+#[test]
+fn system_and_consumer_are_independent() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    kb.event(KeyEvent::pressed(0, 0), 0);
+    kb.event(KeyEvent::pressed(0, 3), 0);
+    kb.event(KeyEvent::released(0, 3), 0);
+    assert_eq!(
+        extra_reports(&mut kb),
+        [
+            [REPORT_ID_SYSTEM, 0x82, 0x00],
+            [REPORT_ID_SYSTEM, 0, 0],
+            consumer(ConsumerKeycode::PlayPause),
+        ]
+    );
+}
+
+#[test]
+fn keyboard_keys_dont_send_extra_reports() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    kb.event(KeyEvent::pressed(0, 2), 0);
+    kb.event(KeyEvent::released(0, 2), 0);
+    assert!(kb.pop_extra_report().is_none());
+}
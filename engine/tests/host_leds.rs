@@ -0,0 +1,35 @@
+use engine::{
+    host_leds::HostLeds,
+    keyboard::{Config, Keyboard},
+    keycode::{qmk::*, Keycode},
+};
+
+static KEYMAP: [[[Keycode; 1]; 1]; 1] = [[[KC_CAPS]]];
+
+#[test]
+fn parses_set_report() {
+    let leds = HostLeds::from_report(&[0x02]).unwrap();
+    assert!(leds.caps_lock());
+    assert!(!leds.num_lock());
+    assert!(!leds.scroll_lock());
+
+    let leds = HostLeds::from_report(&[0x1d]).unwrap();
+    assert!(leds.num_lock());
+    assert!(!leds.caps_lock());
+    assert!(leds.scroll_lock());
+    assert!(leds.compose());
+    assert!(leds.kana());
+}
+
+#[test]
+fn ignores_empty_report() {
+    assert_eq!(HostLeds::from_report(&[]), None);
+}
+
+#[test]
+fn keyboard_exposes_host_leds() {
+    let mut kb: Keyboard<1, 1> = Keyboard::new(&KEYMAP, Config::new());
+    assert!(!kb.host_leds().caps_lock());
+    kb.set_host_leds(HostLeds::from_report(&[HostLeds::CAPS_LOCK]).unwrap());
+    assert!(kb.host_leds().caps_lock());
+}
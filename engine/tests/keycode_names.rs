@@ -0,0 +1,129 @@
+use engine::keycode::{qmk::*, HidKeycode, Keycode, ModifiedKeycode, Mods, ParseKeycodeError};
+
+fn parse(s: &str) -> Result<Keycode, ParseKeycodeError> {
+    s.parse()
+}
+
+/// The names of all keycode constants in `qmk`.
+fn qmk_names() -> Vec<&'static str> {
+    include_str!("../src/keycode/qmk.rs")
+        .lines()
+        .filter_map(|line| line.strip_prefix("pub const "))
+        .filter_map(|rest| rest.split_once(": Keycode ="))
+        .map(|(name, _)| name)
+        .collect()
+}
+
+#[test]
+fn every_alias_round_trips() {
+    let names = qmk_names();
+    assert!(names.len() > 300);
+    for name in names {
+        let keycode = parse(name).unwrap_or_else(|e| panic!("{name}: {e}"));
+        let display = keycode.to_string();
+        assert!(parse(&display) == Ok(keycode), "{name} -> {display}");
+    }
+}
+
+#[test]
+fn names() {
+    assert!(parse("KC_LBRC") == Ok(KC_LBRC));
+    assert!(parse("KC_LBRACKET") == Ok(KC_LBRC));
+    assert!(parse(" KC_A ") == Ok(KC_A));
+    assert!(parse("_______") == Ok(KC_TRNS));
+
+    // Short names are preferred.
+    assert_eq!(KC_LBRACKET.to_string(), "KC_LBRC");
+    assert_eq!(KC_ENTER.to_string(), "KC_ENT");
+    assert_eq!(KC_TRANSPARENT.to_string(), "KC_TRNS");
+    assert_eq!(XXXXXXX.to_string(), "KC_NO");
+    assert_eq!(KC_AUDIO_MUTE.to_string(), "KC_MUTE");
+}
+
+#[test]
+fn layer_functions() {
+    assert!(parse("MO(1)") == Ok(MO(1)));
+    assert!(parse("TG( 31 )") == Ok(TG(31)));
+    assert!(parse("LT(2,KC_SPC)") == Ok(LT(2, KC_SPC)));
+    assert_eq!(LT(2, KC_SPACE).to_string(), "LT(2, KC_SPC)");
+
+    let functions = [MO, OSL, TG, TO, DF];
+    for function in functions {
+        for layer in 0..32 {
+            let keycode = function(layer);
+            assert!(parse(&keycode.to_string()) == Ok(keycode));
+        }
+    }
+}
+
+#[test]
+fn mod_tap_functions() {
+    assert!(parse("LCTL_T(KC_ESC)") == Ok(LCTL_T(KC_ESC)));
+    assert_eq!(CTL_T(KC_ESC).to_string(), "LCTL_T(KC_ESC)");
+    assert_eq!(
+        MT(MOD_LCTL.union(MOD_LSFT), KC_A).to_string(),
+        "C_S_T(KC_A)"
+    );
+    assert!(parse("MT(MOD_LCTL | MOD_LSFT, KC_A)") == Ok(C_S_T(KC_A)));
+    assert_eq!(
+        MT(MOD_LCTL.union(MOD_RGUI), KC_A).to_string(),
+        "MT(MOD_LCTL | MOD_RGUI, KC_A)"
+    );
+
+    for bits in 0..=255 {
+        let keycode = MT(Mods::from_bits(bits), KC_A);
+        assert!(parse(&keycode.to_string()) == Ok(keycode));
+    }
+}
+
+#[test]
+fn modifier_functions() {
+    assert!(parse("LCTL(KC_C)") == Ok(LCTL(KC_C)));
+    assert!(parse("C(KC_C)") == Ok(LCTL(KC_C)));
+    assert!(parse("LCTL(LSFT(KC_ESC))") == Ok(LCS(KC_ESC)));
+    assert!(parse("LCTL(KC_EXLM)") == Ok(LCS(KC_1)));
+    assert!(parse("S(KC_1)") == Ok(KC_EXLM));
+    assert_eq!(S(KC_1).to_string(), "KC_EXLM");
+    assert_eq!(KC_LEFT_PAREN.to_string(), "KC_LPRN");
+    assert_eq!(G(KC_C).to_string(), "LGUI(KC_C)");
+    assert_eq!(MEH(KC_ENTER).to_string(), "MEH(KC_ENT)");
+    assert_eq!(LCTL(RGUI(KC_A)).to_string(), "LCTL(RGUI(KC_A))");
+
+    for bits in 1..=255 {
+        let keycode: Keycode = ModifiedKeycode::new(Mods::from_bits(bits), HidKeycode::A).into();
+        assert!(parse(&keycode.to_string()) == Ok(keycode));
+    }
+}
+
+#[test]
+fn tap_dances() {
+    assert!(parse("TD(3)") == Ok(TD(3)));
+    assert_eq!(TD(255).to_string(), "TD(255)");
+    assert!(parse("TD(256)") == Err(ParseKeycodeError::InvalidArgument));
+}
+
+#[test]
+fn user_keycodes() {
+    assert!(parse("QK_USER_3") == Ok(Keycode::User(3)));
+    assert_eq!(Keycode::User(3).to_string(), "QK_USER_3");
+}
+
+#[test]
+fn errors() {
+    assert!(parse("KC_FOO") == Err(ParseKeycodeError::UnknownName));
+    assert!(parse("FOO(1)") == Err(ParseKeycodeError::UnknownName));
+    assert!(parse("MO(32)") == Err(ParseKeycodeError::InvalidArgument));
+    assert!(parse("MO(x)") == Err(ParseKeycodeError::InvalidArgument));
+    assert!(parse("LT(1, MO(2))") == Err(ParseKeycodeError::InvalidArgument));
+    assert!(parse("LCTL(MO(2))") == Err(ParseKeycodeError::InvalidArgument));
+    // Arguments that `qmk` functions panic on in const evaluation. build.rs
+    // relies on these being rejected to check keymap.json.
+    assert!(parse("LCTL(KC_MPLY)") == Err(ParseKeycodeError::InvalidArgument));
+    assert!(parse("S(MO(1))") == Err(ParseKeycodeError::InvalidArgument));
+    assert!(parse("MEH(RESET)") == Err(ParseKeycodeError::InvalidArgument));
+    assert!(parse("LSFT_T(KC_EXLM)") == Err(ParseKeycodeError::InvalidArgument));
+    assert!(parse("MT(MOD_FOO, KC_A)") == Err(ParseKeycodeError::InvalidArgument));
+    assert!(parse("QK_USER_256") == Err(ParseKeycodeError::InvalidArgument));
+    assert!(parse("MO(1") == Err(ParseKeycodeError::Syntax));
+    assert!(parse("LT(1)") == Err(ParseKeycodeError::Syntax));
+}
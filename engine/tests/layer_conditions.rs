@@ -0,0 +1,80 @@
+use engine::{
+    keyboard::{Keyboard, LayerCondition},
+    keycode::{qmk::*, HidKeycode, Keycode},
+    layer::LayerState,
+};
+
+mod common;
+
+use common::{keyboard, press, release};
+
+#[rustfmt::skip]
+static KEYMAP: [[[Keycode; 4]; 1]; 5] = [
+    [[MO(1)  , MO(2)  , KC_A   , XXXXXXX]],
+    [[_______, _______, KC_B   , _______]],
+    [[_______, _______, KC_C   , _______]],
+    [[_______, _______, KC_D   , _______]],
+    [[_______, _______, KC_E   , _______]],
+];
+
+static TRI_LAYER: [LayerCondition; 1] = [LayerCondition::tri_layer(1, 2, 3)];
+
+static CHAINED: [LayerCondition; 2] = [
+    LayerCondition::tri_layer(1, 2, 3),
+    LayerCondition {
+        layers: LayerState::single(3),
+        then: 4,
+    },
+];
+
+fn tap_a(keyboard: &mut Keyboard<1, 4>) -> HidKeycode {
+    press(keyboard, 2, 0);
+    let pressed = [
+        HidKeycode::A,
+        HidKeycode::B,
+        HidKeycode::C,
+        HidKeycode::D,
+        HidKeycode::E,
+    ]
+    .into_iter()
+    .find(|&k| keyboard.report().is_pressed(k as u8))
+    .unwrap();
+    release(keyboard, 2, 0);
+    pressed
+}
+
+#[test]
+fn tri_layer() {
+    let mut kb = keyboard(&KEYMAP, |config| config.layer_conditions = &TRI_LAYER);
+    press(&mut kb, 0, 0);
+    assert!(tap_a(&mut kb) == HidKeycode::B);
+    press(&mut kb, 1, 0);
+    assert_eq!(kb.layer_state().bits(), 0b1111);
+    assert!(tap_a(&mut kb) == HidKeycode::D);
+    release(&mut kb, 0, 0);
+    assert_eq!(kb.layer_state().bits(), 0b0101);
+    assert!(tap_a(&mut kb) == HidKeycode::C);
+    release(&mut kb, 1, 0);
+    assert!(tap_a(&mut kb) == HidKeycode::A);
+}
+
+#[test]
+fn no_conditions() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    press(&mut kb, 0, 0);
+    press(&mut kb, 1, 0);
+    assert_eq!(kb.layer_state().bits(), 0b0111);
+    assert!(tap_a(&mut kb) == HidKeycode::C);
+}
+
+#[test]
+fn chained_conditions() {
+    let mut kb = keyboard(&KEYMAP, |config| config.layer_conditions = &CHAINED);
+    press(&mut kb, 0, 0);
+    press(&mut kb, 1, 0);
+    // Layer 3 comes from the first condition, and enables the second one.
+    assert_eq!(kb.layer_state().bits(), 0b11111);
+    assert!(tap_a(&mut kb) == HidKeycode::E);
+    release(&mut kb, 1, 0);
+    assert_eq!(kb.layer_state().bits(), 0b00011);
+}
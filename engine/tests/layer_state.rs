@@ -0,0 +1,59 @@
+use engine::{
+    keyboard::{Config, Keyboard},
+    keycode::{qmk::*, HidKeycode, Keycode},
+    layer::LayerState,
+    matrix::KeyEvent,
+};
+
+#[test]
+fn set_clear_toggle() {
+    let mut state = LayerState::EMPTY;
+    state.set(0);
+    state.set(31);
+    assert_eq!(state.bits(), 0x8000_0001);
+    state.clear(0);
+    assert!(!state.is_active(0));
+    assert!(state.is_active(31));
+    state.toggle(31);
+    state.toggle(17);
+    assert_eq!(state, LayerState::single(17));
+}
+
+#[test]
+fn highest_active() {
+    assert_eq!(LayerState::EMPTY.highest_active(), None);
+    assert_eq!(LayerState::single(0).highest_active(), Some(0));
+    assert_eq!(LayerState::single(3).with(12).highest_active(), Some(12));
+    assert_eq!(LayerState::single(31).with(1).highest_active(), Some(31));
+}
+
+#[test]
+fn iter_active() {
+    let state = LayerState::single(2).with(31).with(9);
+    let mut layers = state.iter_active();
+    assert_eq!(layers.next(), Some(31));
+    assert_eq!(layers.next(), Some(9));
+    assert_eq!(layers.next(), Some(2));
+    assert_eq!(layers.next(), None);
+}
+
+// Layer 20 is reached with MO; layer 31 has a key but is never active.
+static KEYMAP: [[[Keycode; 2]; 1]; 32] = {
+    let mut keymap = [[[_______; 2]; 1]; 32];
+    keymap[0] = [[MO(20), KC_A]];
+    keymap[20] = [[_______, KC_B]];
+    keymap[31] = [[_______, KC_C]];
+    keymap
+};
+
+#[test]
+fn layers_above_eight() {
+    let mut kb: Keyboard<1, 2> = Keyboard::new(&KEYMAP, Config::new());
+    kb.event(KeyEvent::pressed(0, 0), 0);
+    assert_eq!(kb.layer_state().highest_active(), Some(20));
+    kb.event(KeyEvent::pressed(0, 1), 0);
+    assert!(kb.report().is_pressed(HidKeycode::B as u8));
+    kb.event(KeyEvent::released(0, 1), 0);
+    kb.event(KeyEvent::released(0, 0), 0);
+    assert_eq!(kb.layer_state(), LayerState::single(0));
+}
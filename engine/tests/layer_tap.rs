@@ -0,0 +1,91 @@
+use engine::keycode::{qmk::*, HidKeycode, Keycode};
+
+mod common;
+
+use common::{keyboard, press, release, reports};
+
+#[rustfmt::skip]
+static KEYMAP: [[[Keycode; 3]; 1]; 2] = [
+    [[LT(1, KC_SPC), KC_J   , LSFT_T(KC_K)]],
+    [[_______      , KC_DOWN, KC_UP       ]],
+];
+
+const LT_SPC: u8 = 0;
+const KEY_J: u8 = 1;
+const MT_K: u8 = 2;
+
+const SPC: u8 = HidKeycode::Space as u8;
+const J: u8 = HidKeycode::J as u8;
+const DOWN: u8 = HidKeycode::Down as u8;
+const UP: u8 = HidKeycode::Up as u8;
+
+#[test]
+fn tap() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    press(&mut kb, LT_SPC, 0);
+    release(&mut kb, LT_SPC, 50);
+    assert_eq!(reports(&mut kb), [vec![SPC], vec![]]);
+    assert_eq!(kb.layer_state().bits(), 0b01);
+}
+
+#[test]
+fn hold() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    press(&mut kb, LT_SPC, 0);
+    kb.tick(200);
+    assert_eq!(kb.layer_state().bits(), 0b11);
+    press(&mut kb, KEY_J, 250);
+    release(&mut kb, LT_SPC, 260);
+    assert_eq!(kb.layer_state().bits(), 0b01);
+    release(&mut kb, KEY_J, 270);
+    assert_eq!(reports(&mut kb), [vec![DOWN], vec![]]);
+}
+
+#[test]
+fn rolling_within_term_is_tap() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    press(&mut kb, LT_SPC, 0);
+    press(&mut kb, KEY_J, 20);
+    release(&mut kb, LT_SPC, 40);
+    release(&mut kb, KEY_J, 60);
+    assert_eq!(reports(&mut kb), [vec![SPC], vec![J, SPC], vec![J], vec![]]);
+}
+
+#[test]
+fn permissive_hold() {
+    let mut kb = keyboard(&KEYMAP, |config| config.permissive_hold = true);
+    press(&mut kb, LT_SPC, 0);
+    press(&mut kb, KEY_J, 20);
+    release(&mut kb, KEY_J, 40);
+    release(&mut kb, LT_SPC, 60);
+    assert_eq!(reports(&mut kb), [vec![DOWN], vec![]]);
+}
+
+#[test]
+fn hold_on_other_key_press() {
+    let mut kb = keyboard(&KEYMAP, |config| config.hold_on_other_key_press = true);
+    press(&mut kb, LT_SPC, 0);
+    press(&mut kb, KEY_J, 20);
+    assert_eq!(reports(&mut kb), [vec![DOWN]]);
+}
+
+#[test]
+fn retro_tapping() {
+    let mut kb = keyboard(&KEYMAP, |config| config.retro_tapping = true);
+    press(&mut kb, LT_SPC, 0);
+    kb.tick(300);
+    release(&mut kb, LT_SPC, 400);
+    assert_eq!(reports(&mut kb), [vec![SPC], vec![]]);
+}
+
+#[test]
+fn deferred_keys_see_held_layer() {
+    // The key pressed while the layer-tap key was undecided is looked up
+    // after the decision, on the layer it activated instead of as the
+    // mod-tap key below it.
+    let mut kb = keyboard(&KEYMAP, |config| config.hold_on_other_key_press = true);
+    press(&mut kb, LT_SPC, 0);
+    press(&mut kb, MT_K, 20);
+    release(&mut kb, MT_K, 40);
+    assert_eq!(reports(&mut kb), [vec![UP], vec![]]);
+}
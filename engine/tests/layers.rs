@@ -0,0 +1,121 @@
+use engine::keycode::{qmk::*, HidKeycode, Keycode};
+
+mod common;
+
+use common::{is_pressed, keyboard, press, release};
+
+#[rustfmt::skip]
+static KEYMAP: [[[Keycode; 4]; 1]; 4] = [
+    [[KC_A   , MO(1)  , TG(2)  , TO(3)  ]],
+    [[KC_B   , _______, _______, DF(2)  ]],
+    [[KC_C   , _______, _______, TO(3)  ]],
+    [[_______, _______, TO(0)  , KC_D   ]],
+];
+
+#[test]
+fn base_layer() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    press(&mut kb, 0, 0);
+    assert!(is_pressed(&kb, HidKeycode::A));
+    release(&mut kb, 0, 0);
+    assert!(!is_pressed(&kb, HidKeycode::A));
+}
+
+#[test]
+fn momentary() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    press(&mut kb, 1, 0);
+    press(&mut kb, 0, 0);
+    assert!(is_pressed(&kb, HidKeycode::B));
+    release(&mut kb, 0, 0);
+    release(&mut kb, 1, 0);
+    press(&mut kb, 0, 0);
+    assert!(is_pressed(&kb, HidKeycode::A));
+    assert!(!is_pressed(&kb, HidKeycode::B));
+}
+
+#[test]
+fn release_after_layer_change() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    press(&mut kb, 1, 0);
+    press(&mut kb, 0, 0);
+    assert!(is_pressed(&kb, HidKeycode::B));
+    release(&mut kb, 1, 0);
+    // Still held, on the layer it was pressed on.
+    assert!(is_pressed(&kb, HidKeycode::B));
+    release(&mut kb, 0, 0);
+    assert!(!is_pressed(&kb, HidKeycode::B));
+    assert!(!is_pressed(&kb, HidKeycode::A));
+}
+
+#[test]
+fn layer_key_released_from_source_layer() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    // MO(1) is released after DF(2) swapped out the base layer it was
+    // pressed on, which has nothing at that position.
+    press(&mut kb, 1, 0);
+    press(&mut kb, 3, 0);
+    release(&mut kb, 3, 0);
+    assert_eq!(kb.layer_state().bits(), 0b0110);
+    release(&mut kb, 1, 0);
+    assert_eq!(kb.layer_state().bits(), 0b0100);
+}
+
+#[test]
+fn toggle() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    press(&mut kb, 2, 0);
+    release(&mut kb, 2, 0);
+    press(&mut kb, 0, 0);
+    assert!(is_pressed(&kb, HidKeycode::C));
+    release(&mut kb, 0, 0);
+    press(&mut kb, 2, 0);
+    release(&mut kb, 2, 0);
+    press(&mut kb, 0, 0);
+    assert!(is_pressed(&kb, HidKeycode::A));
+}
+
+#[test]
+fn to_replaces_stacked_layers() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    press(&mut kb, 2, 0);
+    release(&mut kb, 2, 0);
+    assert_eq!(kb.layer_state().bits(), 0b0101);
+    press(&mut kb, 3, 0);
+    release(&mut kb, 3, 0);
+    assert_eq!(kb.layer_state().bits(), 0b1001);
+    press(&mut kb, 3, 0);
+    assert!(is_pressed(&kb, HidKeycode::D));
+    release(&mut kb, 3, 0);
+    press(&mut kb, 2, 0);
+    release(&mut kb, 2, 0);
+    assert_eq!(kb.layer_state().bits(), 0b0001);
+}
+
+#[test]
+fn default_layer_survives_layers_above() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    press(&mut kb, 1, 0);
+    press(&mut kb, 3, 0);
+    release(&mut kb, 3, 0);
+    release(&mut kb, 1, 0);
+    assert_eq!(kb.layer_state().bits(), 0b0100);
+
+    press(&mut kb, 0, 0);
+    assert!(is_pressed(&kb, HidKeycode::C));
+    release(&mut kb, 0, 0);
+
+    // TO stacks on top of the new default layer.
+    press(&mut kb, 3, 0);
+    release(&mut kb, 3, 0);
+    assert_eq!(kb.layer_state().bits(), 0b1100);
+    press(&mut kb, 0, 0);
+    assert!(is_pressed(&kb, HidKeycode::C));
+    release(&mut kb, 0, 0);
+
+    press(&mut kb, 2, 0);
+    release(&mut kb, 2, 0);
+    assert_eq!(kb.layer_state().bits(), 0b0101);
+    press(&mut kb, 0, 0);
+    assert!(is_pressed(&kb, HidKeycode::C));
+}
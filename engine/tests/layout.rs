@@ -0,0 +1,64 @@
+use engine::{
+    keycode::{qmk::*, Keycode},
+    keymap, layout_planck_2x2u, layout_planck_grid, layout_planck_mit,
+};
+
+keymap! {
+    static LAYERS: [[Keycode; 12]; 4] = {
+        BASE => layout_planck_mit![
+            KC_TAB , KC_Q   , KC_W   , KC_E   , KC_R   , KC_T   , KC_Y   , KC_U   , KC_I   , KC_O   , KC_P   , KC_BSPC,
+            KC_ESC , KC_A   , KC_S   , KC_D   , KC_F   , KC_G   , KC_H   , KC_J   , KC_K   , KC_L   , KC_SCLN, KC_QUOT,
+            KC_LSFT, KC_Z   , KC_X   , KC_C   , KC_V   , KC_B   , KC_N   , KC_M   , KC_COMM, KC_DOT , KC_SLSH, KC_ENT ,
+            KC_LCTL, KC_LGUI, KC_LALT, XXXXXXX, MO(LOWER), KC_SPC    , MO(RAISE), KC_LEFT, KC_DOWN, KC_UP  , KC_RGHT,
+        ],
+        LOWER => layout_planck_2x2u![
+            KC_1   , KC_2   , KC_3   , KC_4   , KC_5   , KC_6   , KC_7   , KC_8   , KC_9   , KC_0   , KC_MINS, KC_EQL ,
+            _______, _______, _______, _______, _______, _______, _______, _______, _______, _______, _______, _______,
+            _______, _______, _______, _______, _______, _______, _______, _______, _______, _______, _______, _______,
+            _______, _______, _______, _______, KC_BSPC         , KC_SPC          , _______, _______, _______, _______,
+        ],
+        RAISE => layout_planck_grid![
+            KC_F1  , KC_F2  , KC_F3  , KC_F4  , KC_F5  , KC_F6  , KC_F7  , KC_F8  , KC_F9  , KC_F10 , KC_F11 , KC_F12 ,
+            _______, _______, _______, _______, _______, _______, _______, _______, _______, _______, _______, _______,
+            _______, _______, _______, _______, _______, _______, _______, _______, _______, _______, _______, _______,
+            _______, _______, _______, _______, _______, KC_HOME, KC_END , _______, _______, _______, _______, _______,
+        ],
+    };
+}
+
+#[test]
+fn named_layers() {
+    assert_eq!((BASE, LOWER, RAISE), (0, 1, 2));
+    assert_eq!(LAYERS.len(), 3);
+    assert!(LAYERS[0][3][4] == MO(LOWER));
+    assert!(LAYERS[0][3][7] == MO(RAISE));
+}
+
+#[test]
+fn grid() {
+    assert!(LAYERS[RAISE as usize][0][0] == KC_F1);
+    assert!(LAYERS[RAISE as usize][0][11] == KC_F12);
+    assert!(LAYERS[RAISE as usize][3][5] == KC_HOME);
+    assert!(LAYERS[RAISE as usize][3][6] == KC_END);
+}
+
+#[test]
+fn mit_spacebar_covers_two_positions() {
+    let bottom = &LAYERS[BASE as usize][3];
+    assert!(bottom[4] == MO(LOWER));
+    assert!(bottom[5] == KC_SPC);
+    assert!(bottom[6] == KC_SPC);
+    assert!(bottom[7] == MO(RAISE));
+    assert!(bottom[11] == KC_RGHT);
+}
+
+#[test]
+fn two_2u_keys_cover_two_positions_each() {
+    let bottom = &LAYERS[LOWER as usize][3];
+    assert!(bottom[3] == _______);
+    assert!(bottom[4] == KC_BSPC);
+    assert!(bottom[5] == KC_BSPC);
+    assert!(bottom[6] == KC_SPC);
+    assert!(bottom[7] == KC_SPC);
+    assert!(bottom[8] == _______);
+}
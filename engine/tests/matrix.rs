@@ -0,0 +1,43 @@
+use engine::matrix::{KeyEvent, Matrix};
+
+fn update(matrix: &mut Matrix<2>, scan: [u16; 2]) -> Vec<KeyEvent> {
+    let mut events = Vec::new();
+    matrix.update(&scan, |event| events.push(event));
+    events
+}
+
+#[test]
+fn reports_changes_only() {
+    let mut matrix = Matrix::new();
+    assert!(update(&mut matrix, [0, 0]).is_empty());
+
+    let events = update(&mut matrix, [0b100, 0b1]);
+    assert!(events == [KeyEvent::pressed(0, 2), KeyEvent::pressed(1, 0)]);
+    assert!(matrix.is_pressed(0, 2));
+
+    assert!(update(&mut matrix, [0b100, 0b1]).is_empty());
+
+    let events = update(&mut matrix, [0b100, 0b1000_0000_0000_0000]);
+    assert!(events == [KeyEvent::released(1, 0), KeyEvent::pressed(1, 15)]);
+    assert!(!matrix.is_pressed(1, 0));
+}
+
+#[test]
+fn refused_events_are_repeated() {
+    let mut matrix = Matrix::<2>::new();
+    let mut events = Vec::new();
+    matrix.try_update(&[0b11, 0b1], |event| {
+        if events.len() == 1 {
+            return false;
+        }
+        events.push(event);
+        true
+    });
+    assert!(events == [KeyEvent::pressed(0, 0)]);
+    assert!(matrix.is_pressed(0, 0));
+    assert!(!matrix.is_pressed(0, 1));
+    assert!(!matrix.is_pressed(1, 0));
+
+    let events = update(&mut matrix, [0b11, 0b1]);
+    assert!(events == [KeyEvent::pressed(0, 1), KeyEvent::pressed(1, 0)]);
+}
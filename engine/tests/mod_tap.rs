@@ -0,0 +1,168 @@
+use engine::keycode::{qmk::*, HidKeycode, Keycode};
+
+mod common;
+
+use common::{keyboard, press, release, reports};
+
+#[rustfmt::skip]
+static KEYMAP: [[[Keycode; 3]; 1]; 1] = [
+    [[LSFT_T(KC_A), KC_B, CTL_T(KC_ESC)]],
+];
+
+const MT_A: u8 = 0;
+const KEY_B: u8 = 1;
+const MT_ESC: u8 = 2;
+
+const A: u8 = HidKeycode::A as u8;
+const B: u8 = HidKeycode::B as u8;
+const ESC: u8 = HidKeycode::Escape as u8;
+const LSFT: u8 = HidKeycode::LeftShift as u8;
+const LCTL: u8 = HidKeycode::LeftControl as u8;
+
+#[test]
+fn tap() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    press(&mut kb, MT_A, 0);
+    assert!(reports(&mut kb).is_empty());
+    release(&mut kb, MT_A, 50);
+    assert_eq!(reports(&mut kb), [vec![A], vec![]]);
+}
+
+#[test]
+fn hold() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    press(&mut kb, MT_A, 0);
+    kb.tick(199);
+    assert!(reports(&mut kb).is_empty());
+    kb.tick(200);
+    assert_eq!(reports(&mut kb), [vec![LSFT]]);
+    press(&mut kb, KEY_B, 250);
+    release(&mut kb, KEY_B, 260);
+    release(&mut kb, MT_A, 270);
+    assert_eq!(reports(&mut kb), [vec![B, LSFT], vec![LSFT], vec![]]);
+}
+
+#[test]
+fn hold_decided_by_late_event() {
+    // No tick in between; the next event reveals that the term has passed.
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    press(&mut kb, MT_A, 0);
+    press(&mut kb, KEY_B, 300);
+    assert_eq!(reports(&mut kb), [vec![LSFT], vec![B, LSFT]]);
+}
+
+#[test]
+fn rolling_within_term_is_tap() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    press(&mut kb, MT_A, 0);
+    press(&mut kb, KEY_B, 20);
+    release(&mut kb, MT_A, 40);
+    release(&mut kb, KEY_B, 60);
+    assert_eq!(reports(&mut kb), [vec![A], vec![A, B], vec![B], vec![]]);
+}
+
+#[test]
+fn nested_within_term_is_tap_by_default() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    press(&mut kb, MT_A, 0);
+    press(&mut kb, KEY_B, 20);
+    release(&mut kb, KEY_B, 40);
+    assert!(reports(&mut kb).is_empty());
+    release(&mut kb, MT_A, 60);
+    assert_eq!(reports(&mut kb), [vec![A], vec![A, B], vec![A], vec![]]);
+}
+
+#[test]
+fn permissive_hold() {
+    let mut kb = keyboard(&KEYMAP, |config| config.permissive_hold = true);
+    press(&mut kb, MT_A, 0);
+    press(&mut kb, KEY_B, 20);
+    assert!(reports(&mut kb).is_empty());
+    release(&mut kb, KEY_B, 40);
+    assert_eq!(reports(&mut kb), [vec![LSFT], vec![B, LSFT], vec![LSFT]]);
+    release(&mut kb, MT_A, 60);
+    assert_eq!(reports(&mut kb), [vec![]]);
+}
+
+#[test]
+fn permissive_hold_rolling_is_tap() {
+    let mut kb = keyboard(&KEYMAP, |config| config.permissive_hold = true);
+    press(&mut kb, MT_A, 0);
+    press(&mut kb, KEY_B, 20);
+    release(&mut kb, MT_A, 40);
+    assert_eq!(reports(&mut kb), [vec![A], vec![A, B], vec![B]]);
+}
+
+#[test]
+fn hold_on_other_key_press() {
+    let mut kb = keyboard(&KEYMAP, |config| config.hold_on_other_key_press = true);
+    press(&mut kb, MT_A, 0);
+    press(&mut kb, KEY_B, 20);
+    assert_eq!(reports(&mut kb), [vec![LSFT], vec![B, LSFT]]);
+}
+
+#[test]
+fn retro_tapping() {
+    let mut kb = keyboard(&KEYMAP, |config| config.retro_tapping = true);
+    press(&mut kb, MT_A, 0);
+    kb.tick(300);
+    release(&mut kb, MT_A, 400);
+    assert_eq!(reports(&mut kb), [vec![LSFT], vec![], vec![A], vec![]]);
+}
+
+#[test]
+fn retro_tapping_interrupted() {
+    let mut kb = keyboard(&KEYMAP, |config| config.retro_tapping = true);
+    press(&mut kb, MT_A, 0);
+    kb.tick(300);
+    press(&mut kb, KEY_B, 310);
+    release(&mut kb, KEY_B, 320);
+    release(&mut kb, MT_A, 400);
+    assert_eq!(
+        reports(&mut kb),
+        [vec![LSFT], vec![B, LSFT], vec![LSFT], vec![]]
+    );
+}
+
+#[test]
+fn without_retro_tapping() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    press(&mut kb, MT_A, 0);
+    kb.tick(300);
+    release(&mut kb, MT_A, 400);
+    assert_eq!(reports(&mut kb), [vec![LSFT], vec![]]);
+}
+
+#[test]
+fn two_mod_taps_rolled() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    press(&mut kb, MT_A, 0);
+    press(&mut kb, MT_ESC, 10);
+    release(&mut kb, MT_A, 20);
+    release(&mut kb, MT_ESC, 30);
+    assert_eq!(reports(&mut kb), [vec![A], vec![A, ESC], vec![ESC], vec![]]);
+}
+
+#[test]
+fn two_mod_taps_held() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    press(&mut kb, MT_A, 0);
+    press(&mut kb, MT_ESC, 10);
+    kb.tick(200);
+    assert_eq!(reports(&mut kb), [vec![LSFT]]);
+    kb.tick(210);
+    assert_eq!(reports(&mut kb), [vec![LCTL, LSFT]]);
+}
+
+#[test]
+fn deferred_overflow_holds() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    press(&mut kb, MT_A, 0);
+    for t in 0..4 {
+        press(&mut kb, KEY_B, 10 + 2 * t);
+        release(&mut kb, KEY_B, 11 + 2 * t);
+    }
+    assert!(reports(&mut kb).is_empty());
+    press(&mut kb, KEY_B, 20);
+    assert_eq!(reports(&mut kb)[0], [LSFT]);
+}
@@ -0,0 +1,88 @@
+use engine::keycode::{qmk::*, HidKeycode, Keycode};
+
+mod common;
+
+use common::{keyboard, press, release, reports};
+
+#[rustfmt::skip]
+static KEYMAP: [[[Keycode; 4]; 1]; 1] = [
+    [[KC_EXLM, LCTL(LSFT(KC_T)), KC_LSFT, LSFT_T(KC_A)]],
+];
+
+const EXLM: u8 = 0;
+const CS_T: u8 = 1;
+const LSHIFT: u8 = 2;
+const MT_A: u8 = 3;
+
+const ONE: u8 = HidKeycode::Num1 as u8;
+const T: u8 = HidKeycode::T as u8;
+const CTRL: u8 = HidKeycode::LeftControl as u8;
+const SHIFT: u8 = HidKeycode::LeftShift as u8;
+
+#[test]
+fn mods_are_pressed_before_and_released_after_the_key() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    press(&mut kb, EXLM, 0);
+    release(&mut kb, EXLM, 10);
+    assert_eq!(
+        reports(&mut kb),
+        [vec![SHIFT], vec![ONE, SHIFT], vec![SHIFT], vec![]]
+    );
+
+    press(&mut kb, CS_T, 20);
+    release(&mut kb, CS_T, 30);
+    assert_eq!(
+        reports(&mut kb),
+        [
+            vec![CTRL, SHIFT],
+            vec![T, CTRL, SHIFT],
+            vec![CTRL, SHIFT],
+            vec![]
+        ]
+    );
+}
+
+#[test]
+fn held_modifier_key_is_kept() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    press(&mut kb, LSHIFT, 0);
+    press(&mut kb, EXLM, 10);
+    release(&mut kb, EXLM, 20);
+    assert_eq!(
+        reports(&mut kb),
+        [vec![SHIFT], vec![ONE, SHIFT], vec![SHIFT]]
+    );
+    assert!(kb.report().is_pressed(SHIFT));
+    release(&mut kb, LSHIFT, 30);
+    assert_eq!(reports(&mut kb), [vec![]]);
+}
+
+#[test]
+fn modifier_key_released_first() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    press(&mut kb, EXLM, 0);
+    press(&mut kb, LSHIFT, 10);
+    release(&mut kb, LSHIFT, 20);
+    // Still held by the modified key.
+    assert!(kb.report().is_pressed(SHIFT));
+    release(&mut kb, EXLM, 30);
+    assert_eq!(
+        reports(&mut kb),
+        [vec![SHIFT], vec![ONE, SHIFT], vec![SHIFT], vec![]]
+    );
+}
+
+#[test]
+fn held_mod_tap_is_kept() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    press(&mut kb, MT_A, 0);
+    kb.tick(200);
+    press(&mut kb, EXLM, 210);
+    release(&mut kb, EXLM, 220);
+    assert!(kb.report().is_pressed(SHIFT));
+    release(&mut kb, MT_A, 230);
+    assert_eq!(
+        reports(&mut kb),
+        [vec![SHIFT], vec![ONE, SHIFT], vec![SHIFT], vec![]]
+    );
+}
@@ -0,0 +1,134 @@
+use engine::{
+    keyboard::{Config, Keyboard},
+    keycode::{qmk::*, Keycode, MouseKeycode},
+    matrix::KeyEvent,
+    mousekey::{Acceleration, Curve, MouseKeys, MouseReport},
+};
+
+#[rustfmt::skip]
+static KEYMAP: [[[Keycode; 4]; 1]; 1] = [
+    [[KC_MS_U, KC_BTN1, KC_WH_D, KC_ACL0]],
+];
+
+const ACCELERATION: Acceleration = Acceleration {
+    delay: 50,
+    interval: 10,
+    delta: 2,
+    max_speed: 10,
+    time_to_max: 4,
+    curve: Curve::Linear,
+};
+
+fn fields(report: MouseReport) -> (u8, i8, i8, i8, i8) {
+    (report.buttons, report.x, report.y, report.wheel, report.pan)
+}
+
+fn moves(mouse: &mut MouseKeys, from: u16, to: u16) -> Vec<(u16, i8, i8)> {
+    (from..=to)
+        .filter_map(|now| mouse.tick(now).map(|report| (now, report.x, report.y)))
+        .collect()
+}
+
+#[test]
+fn linear_distance() {
+    let distances: Vec<u8> = (0..6).map(|i| ACCELERATION.distance(i, None)).collect();
+    // Never slower than the first move.
+    assert_eq!(distances, [2, 5, 10, 15, 20, 20]);
+}
+
+#[test]
+fn quadratic_distance() {
+    let acceleration = Acceleration {
+        curve: Curve::Quadratic,
+        ..ACCELERATION
+    };
+    let distances: Vec<u8> = (0..6).map(|i| acceleration.distance(i, None)).collect();
+    assert_eq!(distances, [2, 2, 5, 11, 20, 20]);
+}
+
+#[test]
+fn accelerator_keys_fix_speed() {
+    assert_eq!(ACCELERATION.distance(0, Some(0)), 5);
+    assert_eq!(ACCELERATION.distance(9, Some(1)), 10);
+    assert_eq!(ACCELERATION.distance(1, Some(2)), 20);
+}
+
+#[test]
+fn distance_fits_report() {
+    let acceleration = Acceleration {
+        delta: 100,
+        ..ACCELERATION
+    };
+    assert_eq!(acceleration.distance(10, None), 127);
+}
+
+#[test]
+fn repeats_after_delay_then_interval() {
+    let mut mouse = MouseKeys::new(ACCELERATION, Acceleration::WHEEL);
+    let report = mouse.press(MouseKeycode::Right, 100).unwrap();
+    assert_eq!(fields(report), (0, 2, 0, 0, 0));
+    assert_eq!(
+        moves(&mut mouse, 101, 180),
+        [(150, 5, 0), (160, 10, 0), (170, 15, 0), (180, 20, 0)]
+    );
+    assert!(mouse.release(MouseKeycode::Right).is_none());
+    assert!(mouse.tick(300).is_none());
+}
+
+#[test]
+fn diagonal() {
+    let mut mouse = MouseKeys::new(ACCELERATION, Acceleration::WHEEL);
+    mouse.press(MouseKeycode::Left, 0);
+    let report = mouse.press(MouseKeycode::Up, 0).unwrap();
+    assert_eq!((report.x, report.y), (-1, -1));
+    assert_eq!(moves(&mut mouse, 1, 60), [(50, -3, -3), (60, -7, -7)]);
+}
+
+#[test]
+fn buttons() {
+    let mut mouse = MouseKeys::new(ACCELERATION, Acceleration::WHEEL);
+    let report = mouse.press(MouseKeycode::Button1, 0).unwrap();
+    assert_eq!(fields(report), (0b0000_0001, 0, 0, 0, 0));
+    let report = mouse.press(MouseKeycode::Button8, 0).unwrap();
+    assert_eq!(fields(report), (0b1000_0001, 0, 0, 0, 0));
+    let report = mouse.release(MouseKeycode::Button1).unwrap();
+    assert_eq!(fields(report), (0b1000_0000, 0, 0, 0, 0));
+    // Buttons don't repeat.
+    assert!(mouse.tick(1000).is_none());
+}
+
+#[test]
+fn wheel_and_pan() {
+    let mut mouse = MouseKeys::new(ACCELERATION, ACCELERATION);
+    let report = mouse.press(MouseKeycode::WheelUp, 0).unwrap();
+    assert_eq!(fields(report), (0, 0, 0, 2, 0));
+    let report = mouse.press(MouseKeycode::WheelLeft, 0).unwrap();
+    assert_eq!(fields(report), (0, 0, 0, 2, -2));
+}
+
+#[test]
+fn keyboard_sends_mouse_reports() {
+    let mut kb: Keyboard<1, 4> = Keyboard::new(&KEYMAP, Config::new());
+    kb.event(KeyEvent::pressed(0, 3), 0);
+    kb.event(KeyEvent::pressed(0, 1), 0);
+    kb.event(KeyEvent::pressed(0, 0), 0);
+    kb.tick(10);
+    kb.event(KeyEvent::released(0, 0), 20);
+    kb.event(KeyEvent::released(0, 1), 20);
+    kb.event(KeyEvent::pressed(0, 2), 20);
+    let reports: Vec<_> = std::iter::from_fn(|| kb.pop_mouse_report())
+        .map(fields)
+        .collect();
+    assert_eq!(
+        reports,
+        [
+            (1, 0, 0, 0, 0),
+            (1, 0, -20, 0, 0),
+            (1, 0, -20, 0, 0),
+            (0, 0, 0, 0, 0),
+            (0, 0, 0, -2, 0),
+        ]
+    );
+    // Mouse keys don't touch the keyboard report.
+    assert!(kb.pop_report().is_none());
+}
@@ -0,0 +1,133 @@
+use engine::keycode::{qmk::*, HidKeycode, Keycode};
+
+mod common;
+
+use common::{is_pressed, keyboard, press, release, reports};
+
+#[rustfmt::skip]
+static KEYMAP: [[[Keycode; 3]; 1]; 2] = [
+    [[OSL(1) , KC_A   , KC_B   ]],
+    [[_______, KC_X   , KC_Y   ]],
+];
+
+const TIMEOUT: u16 = 1000;
+
+const A: u8 = HidKeycode::A as u8;
+const X: u8 = HidKeycode::X as u8;
+const Y: u8 = HidKeycode::Y as u8;
+
+#[test]
+fn tap_applies_to_next_key_only() {
+    let mut kb = keyboard(&KEYMAP, |config| config.oneshot_timeout = Some(TIMEOUT));
+    press(&mut kb, 0, 0);
+    release(&mut kb, 0, 10);
+
+    press(&mut kb, 1, 20);
+    assert!(is_pressed(&kb, HidKeycode::X));
+    assert!(!is_pressed(&kb, HidKeycode::A));
+    assert_eq!(kb.layer_state().bits(), 0b01);
+    press(&mut kb, 2, 25);
+    assert!(is_pressed(&kb, HidKeycode::B));
+    release(&mut kb, 2, 28);
+    release(&mut kb, 1, 30);
+    assert!(!is_pressed(&kb, HidKeycode::X));
+
+    press(&mut kb, 1, 40);
+    assert!(is_pressed(&kb, HidKeycode::A));
+    assert!(!is_pressed(&kb, HidKeycode::X));
+}
+
+#[test]
+fn hold_acts_as_momentary() {
+    let mut kb = keyboard(&KEYMAP, |config| config.oneshot_timeout = Some(TIMEOUT));
+    press(&mut kb, 0, 0);
+    press(&mut kb, 1, 10);
+    assert!(is_pressed(&kb, HidKeycode::X));
+    release(&mut kb, 1, 20);
+    press(&mut kb, 2, 30);
+    assert!(is_pressed(&kb, HidKeycode::Y));
+    release(&mut kb, 2, 40);
+    release(&mut kb, 0, 50);
+
+    press(&mut kb, 1, 60);
+    assert!(is_pressed(&kb, HidKeycode::A));
+}
+
+#[test]
+fn timeout_cancels() {
+    let mut kb = keyboard(&KEYMAP, |config| config.oneshot_timeout = Some(TIMEOUT));
+    press(&mut kb, 0, 0);
+    release(&mut kb, 0, 10);
+
+    kb.tick(10 + TIMEOUT - 1);
+    assert_eq!(kb.layer_state().bits(), 0b11);
+    kb.tick(10 + TIMEOUT);
+    assert_eq!(kb.layer_state().bits(), 0b01);
+
+    press(&mut kb, 1, 20 + TIMEOUT);
+    assert!(is_pressed(&kb, HidKeycode::A));
+}
+
+#[test]
+fn timeout_across_clock_wrap() {
+    let mut kb = keyboard(&KEYMAP, |config| config.oneshot_timeout = Some(TIMEOUT));
+    let start = u16::MAX - 100;
+    press(&mut kb, 0, start);
+    release(&mut kb, 0, start);
+
+    kb.tick(start.wrapping_add(TIMEOUT - 1));
+    assert_eq!(kb.layer_state().bits(), 0b11);
+    kb.tick(start.wrapping_add(TIMEOUT));
+    assert_eq!(kb.layer_state().bits(), 0b01);
+}
+
+#[test]
+fn no_timeout() {
+    let mut kb = keyboard(&KEYMAP, |config| config.oneshot_timeout = None);
+    press(&mut kb, 0, 0);
+    release(&mut kb, 0, 10);
+    kb.tick(30000);
+    press(&mut kb, 2, 30000);
+    assert!(is_pressed(&kb, HidKeycode::Y));
+}
+
+#[test]
+fn tap_reports() {
+    let mut kb = keyboard(&KEYMAP, |config| config.oneshot_timeout = Some(TIMEOUT));
+    press(&mut kb, 0, 0);
+    release(&mut kb, 0, 10);
+    assert!(reports(&mut kb).is_empty());
+
+    press(&mut kb, 1, 20);
+    release(&mut kb, 1, 30);
+    press(&mut kb, 1, 40);
+    release(&mut kb, 1, 50);
+    assert_eq!(reports(&mut kb), [vec![X], vec![], vec![A], vec![]]);
+}
+
+#[test]
+fn hold_reports() {
+    let mut kb = keyboard(&KEYMAP, |config| config.oneshot_timeout = Some(TIMEOUT));
+    press(&mut kb, 0, 0);
+    press(&mut kb, 1, 10);
+    release(&mut kb, 1, 20);
+    press(&mut kb, 2, 30);
+    release(&mut kb, 2, 40);
+    release(&mut kb, 0, 50);
+    press(&mut kb, 1, 60);
+    release(&mut kb, 1, 70);
+    assert_eq!(
+        reports(&mut kb),
+        [vec![X], vec![], vec![Y], vec![], vec![A], vec![]]
+    );
+}
+
+#[test]
+fn timeout_reports() {
+    let mut kb = keyboard(&KEYMAP, |config| config.oneshot_timeout = Some(TIMEOUT));
+    press(&mut kb, 0, 0);
+    release(&mut kb, 0, 10);
+    press(&mut kb, 1, 10 + TIMEOUT);
+    release(&mut kb, 1, 20 + TIMEOUT);
+    assert_eq!(reports(&mut kb), [vec![A], vec![]]);
+}
@@ -0,0 +1,89 @@
+use engine::keycode::{qmk::*, HidKeycode, Keycode, QmkKeycodeError};
+
+fn encode(keycode: Keycode) -> Result<u16, QmkKeycodeError> {
+    keycode.to_qmk_u16()
+}
+
+#[test]
+fn known_codes() {
+    assert_eq!(encode(KC_NO), Ok(0x0000));
+    assert_eq!(encode(KC_TRNS), Ok(0x0001));
+    assert_eq!(encode(KC_A), Ok(0x0004));
+    assert_eq!(encode(KC_EXSEL), Ok(0x00a4));
+    assert_eq!(encode(KC_RGUI), Ok(0x00e7));
+    assert_eq!(encode(KC_PWR), Ok(0x00a5));
+    assert_eq!(encode(KC_MUTE), Ok(0x00a8));
+    assert_eq!(encode(KC_BRID), Ok(0x00be));
+    assert_eq!(encode(KC_MS_U), Ok(0x00cd));
+    assert_eq!(encode(KC_ACL2), Ok(0x00df));
+    assert_eq!(encode(LCTL(KC_C)), Ok(0x0106));
+    assert_eq!(encode(KC_EXLM), Ok(0x021e));
+    assert_eq!(encode(RALT(KC_E)), Ok(0x1408));
+    assert_eq!(encode(HYPR(KC_F1)), Ok(0x0f3a));
+    assert_eq!(encode(LCTL_T(KC_A)), Ok(0x2104));
+    assert_eq!(encode(RCTL_T(KC_A)), Ok(0x3104));
+    assert_eq!(encode(MEH_T(KC_ESC)), Ok(0x2729));
+    assert_eq!(encode(LT(1, KC_SPC)), Ok(0x412c));
+    assert_eq!(encode(TO(31)), Ok(0x521f));
+    assert_eq!(encode(MO(0)), Ok(0x5220));
+    assert_eq!(encode(DF(2)), Ok(0x5242));
+    assert_eq!(encode(TG(3)), Ok(0x5263));
+    assert_eq!(encode(OSL(1)), Ok(0x5281));
+    assert_eq!(encode(TD(0)), Ok(0x5700));
+    assert_eq!(encode(TD(255)), Ok(0x57ff));
+    assert_eq!(encode(BL_TOGG), Ok(0x7802));
+    assert_eq!(encode(RESET), Ok(0x7c00));
+    assert_eq!(encode(Keycode::User(2)), Ok(0x7e42));
+}
+
+#[test]
+fn unrepresentable() {
+    let error = Err(QmkKeycodeError::Unrepresentable);
+    assert_eq!(encode(LT(16, KC_A)), error);
+    assert_eq!(encode(MT(MOD_LCTL.union(MOD_RSFT), KC_A)), error);
+    assert_eq!(encode(LCTL(RSFT(KC_A))), error);
+    assert_eq!(encode(KC_ROLL_OVER), error);
+    assert_eq!(encode(Keycode::Hid(HidKeycode::KeypadXor)), error);
+    assert_eq!(encode(LCTL_T(Keycode::Hid(HidKeycode::Keypad00))), error);
+}
+
+#[test]
+fn unsupported() {
+    let error = Err(QmkKeycodeError::Unsupported);
+    assert!(Keycode::from_qmk_u16(0x0002) == error);
+    assert!(Keycode::from_qmk_u16(0x00c0) == error);
+    assert!(Keycode::from_qmk_u16(0x1004) == error);
+    assert!(Keycode::from_qmk_u16(0x3004) == error);
+    assert!(Keycode::from_qmk_u16(0x5000) == error);
+    assert!(Keycode::from_qmk_u16(0x52a0) == error);
+    assert!(Keycode::from_qmk_u16(0xffff) == error);
+}
+
+#[test]
+fn every_supported_code_round_trips() {
+    let mut supported = 0;
+    for code in 0..=u16::MAX {
+        if let Ok(keycode) = Keycode::from_qmk_u16(code) {
+            assert_eq!(encode(keycode), Ok(code), "{code:#06x} ({keycode})");
+            supported += 1;
+        }
+    }
+    assert!(supported > 5_000);
+}
+
+#[test]
+fn every_named_keycode_round_trips() {
+    let source = include_str!("../src/keycode/qmk.rs");
+    let names = source
+        .lines()
+        .filter_map(|line| line.strip_prefix("pub const "))
+        .filter_map(|rest| rest.split_once(": Keycode ="))
+        .map(|(name, _)| name);
+    for name in names {
+        let keycode: Keycode = name.parse().unwrap();
+        match encode(keycode) {
+            Ok(code) => assert!(Keycode::from_qmk_u16(code) == Ok(keycode), "{name}"),
+            Err(e) => assert_eq!(e, QmkKeycodeError::Unrepresentable, "{name}"),
+        }
+    }
+}
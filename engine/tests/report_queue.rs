@@ -0,0 +1,74 @@
+use engine::{
+    keyboard::Config,
+    keycode::{qmk::*, HidKeycode, Keycode, MouseKeycode},
+    mousekey::MouseKeys,
+    queue::Queue,
+};
+
+mod common;
+
+use common::{keyboard, press, release, reports};
+
+#[rustfmt::skip]
+static KEYMAP: [[[Keycode; 8]; 1]; 1] = [
+    [[KC_A   , KC_B   , KC_C   , KC_D   , KC_E   , KC_F   , KC_G   , KC_MS_R]],
+];
+
+#[test]
+fn remove_from_wrapped_queue() {
+    let mut queue = Queue::<u8, 4>::new();
+    for i in 0..4 {
+        queue.push(i).unwrap();
+    }
+    queue.pop();
+    queue.push(4).unwrap();
+    assert_eq!(queue.remove(1), Some(2));
+    assert_eq!(queue.get(1), Some(&3));
+    assert!(queue.remove(3).is_none());
+    assert_eq!(queue.iter().copied().collect::<Vec<_>>(), [1, 3, 4]);
+}
+
+#[test]
+fn full_queue_keeps_every_tap() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    // 14 reports, more than fit in the queue.
+    for col in 0..7 {
+        press(&mut kb, col, 0);
+        release(&mut kb, col, 0);
+    }
+    let keys = [
+        HidKeycode::A,
+        HidKeycode::B,
+        HidKeycode::C,
+        HidKeycode::D,
+        HidKeycode::E,
+        HidKeycode::F,
+        HidKeycode::G,
+    ];
+    // Each release is sent with the next press.
+    let mut expected: Vec<Vec<u8>> = keys.iter().map(|&key| vec![key as u8]).collect();
+    expected.push(vec![]);
+    assert_eq!(reports(&mut kb), expected);
+}
+
+#[test]
+fn full_queue_adds_up_mouse_movement() {
+    const TIME: u16 = 300;
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    press(&mut kb, 7, 0);
+    for now in 1..=TIME {
+        kb.tick(now);
+    }
+    let moved: i32 = std::iter::from_fn(|| kb.pop_mouse_report())
+        .map(|report| i32::from(report.x))
+        .sum();
+
+    let config = Config::new();
+    let mut mouse = MouseKeys::new(config.mouse_cursor, config.mouse_wheel);
+    let mut moves = vec![mouse.press(MouseKeycode::Right, 0).unwrap()];
+    moves.extend((1..=TIME).filter_map(|now| mouse.tick(now)));
+    // More reports than fit in the queue, but not more movement.
+    assert!(moves.len() > 8);
+    let expected: i32 = moves.iter().map(|report| i32::from(report.x)).sum();
+    assert_eq!(moved, expected);
+}
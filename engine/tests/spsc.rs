@@ -0,0 +1,78 @@
+use engine::spsc::SpscQueue;
+
+#[test]
+fn fifo_order() {
+    let mut queue = SpscQueue::<u8, 4>::new();
+    let (mut producer, mut consumer) = queue.split();
+    assert!(consumer.is_empty());
+    assert!(consumer.pop().is_none());
+
+    producer.push(1).unwrap();
+    producer.push(2).unwrap();
+    assert_eq!(consumer.len(), 2);
+    assert_eq!(consumer.peek(), Some(1));
+    assert_eq!(consumer.pop(), Some(1));
+    assert_eq!(consumer.pop(), Some(2));
+    assert!(consumer.pop().is_none());
+}
+
+#[test]
+fn overflow_gives_the_value_back() {
+    let mut queue = SpscQueue::<u8, 4>::new();
+    let (mut producer, mut consumer) = queue.split();
+    for i in 0..4 {
+        producer.push(i).unwrap();
+    }
+    assert!(producer.is_full());
+    assert_eq!(producer.push(4), Err(4));
+
+    // The queued values are unaffected.
+    assert_eq!(consumer.pop(), Some(0));
+    producer.push(5).unwrap();
+    assert_eq!(consumer.pop(), Some(1));
+    assert_eq!(consumer.pop(), Some(2));
+    assert_eq!(consumer.pop(), Some(3));
+    assert_eq!(consumer.pop(), Some(5));
+    assert!(consumer.pop().is_none());
+}
+
+#[test]
+fn wraps_around() {
+    let mut queue = SpscQueue::<u16, 4>::new();
+    let (mut producer, mut consumer) = queue.split();
+    for i in 0..1000 {
+        producer.push(i).unwrap();
+        producer.push(i + 1).unwrap();
+        producer.push(i + 2).unwrap();
+        assert_eq!(consumer.pop(), Some(i));
+        assert_eq!(consumer.pop(), Some(i + 1));
+        assert_eq!(consumer.pop(), Some(i + 2));
+    }
+}
+
+#[test]
+fn between_threads() {
+    const COUNT: u32 = 100_000;
+    let mut queue = SpscQueue::<u32, 8>::new();
+    let (mut producer, mut consumer) = queue.split();
+    std::thread::scope(|s| {
+        s.spawn(move || {
+            for i in 0..COUNT {
+                let mut value = i;
+                while let Err(v) = producer.push(value) {
+                    value = v;
+                    std::thread::yield_now();
+                }
+            }
+        });
+        let mut expected = 0;
+        while expected < COUNT {
+            if let Some(value) = consumer.pop() {
+                assert_eq!(value, expected);
+                expected += 1;
+            } else {
+                std::thread::yield_now();
+            }
+        }
+    });
+}
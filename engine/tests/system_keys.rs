@@ -0,0 +1,41 @@
+use engine::{
+    keycode::{qmk::*, Keycode, SystemKeycode},
+    matrix::KeyEvent,
+};
+
+mod common;
+
+use common::keyboard;
+
+#[rustfmt::skip]
+static KEYMAP: [[[Keycode; 3]; 1]; 2] = [
+    [[MO(1)  , KC_A   , BL_STEP]],
+    [[_______, RESET  , _______]],
+];
+
+#[test]
+fn reset_is_queued_on_press() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    kb.event(KeyEvent::pressed(0, 0), 0);
+    kb.event(KeyEvent::pressed(0, 1), 0);
+    assert!(kb.pop_system_key() == Some(SystemKeycode::Reset));
+    kb.event(KeyEvent::released(0, 1), 0);
+    kb.event(KeyEvent::released(0, 0), 0);
+    assert!(kb.pop_system_key().is_none());
+    assert!(kb.pop_report().is_none());
+}
+
+#[test]
+fn presses_beyond_capacity_are_dropped() {
+    let mut kb = keyboard(&KEYMAP, |_| {});
+    for _ in 0..6 {
+        kb.event(KeyEvent::pressed(0, 2), 0);
+        kb.event(KeyEvent::released(0, 2), 0);
+    }
+    let mut count = 0;
+    while let Some(key) = kb.pop_system_key() {
+        assert!(key == SystemKeycode::BacklightStep);
+        count += 1;
+    }
+    assert_eq!(count, 4);
+}
@@ -0,0 +1,234 @@
+use engine::{
+    keyboard::{Config, Keyboard},
+    keycode::{qmk::*, HidKeycode, Keycode},
+    matrix::KeyEvent,
+    tap_dance::{Dance, Decision, Outcome, TapDance},
+};
+
+mod common;
+
+use common::{keyboard, press, release, reports};
+
+static TAP_DANCES: [TapDance; 3] = [
+    TapDance {
+        tap: KC_A,
+        hold: KC_LCTL,
+        double_tap: KC_B,
+        triple_tap: KC_C,
+    },
+    TapDance::double(KC_ESC, KC_CAPS),
+    TapDance::tap_hold(KC_SPC, MO(1)),
+];
+
+#[rustfmt::skip]
+static KEYMAP: [[[Keycode; 4]; 1]; 2] = [
+    [[TD(0), TD(1), TD(2), KC_X]],
+    [[_______, _______, _______, KC_Y]],
+];
+
+const TD_ABC: u8 = 0;
+const TD_ESC: u8 = 1;
+const TD_SPC: u8 = 2;
+const KEY_X: u8 = 3;
+
+const A: u8 = HidKeycode::A as u8;
+const B: u8 = HidKeycode::B as u8;
+const C: u8 = HidKeycode::C as u8;
+const X: u8 = HidKeycode::X as u8;
+const Y: u8 = HidKeycode::Y as u8;
+const ESC: u8 = HidKeycode::Escape as u8;
+const CAPS: u8 = HidKeycode::CapsLock as u8;
+const SPACE: u8 = HidKeycode::Space as u8;
+const CTRL: u8 = HidKeycode::LeftControl as u8;
+
+fn tap(keyboard: &mut Keyboard<1, 4>, col: u8, now: u16) {
+    press(keyboard, col, now);
+    release(keyboard, col, now + 20);
+}
+
+fn decision(outcome: Outcome, held: bool) -> Decision {
+    Decision { outcome, held }
+}
+
+#[test]
+fn dance_waits_for_the_term() {
+    let td = &TAP_DANCES[0];
+    let (mut dance, decision) = Dance::start(0, 0, 0, td, 0);
+    assert_eq!(decision, None);
+    assert_eq!(dance.release(td, 50), None);
+    assert_eq!(dance.tick(td, 200, 249), None);
+    assert_eq!(
+        dance.tick(td, 200, 250),
+        Some(self::decision(Outcome::Taps(1), false))
+    );
+}
+
+#[test]
+fn dance_hold() {
+    let td = &TAP_DANCES[0];
+    let (dance, _) = Dance::start(0, 0, 0, td, 0);
+    assert_eq!(
+        dance.tick(td, 200, 200),
+        Some(decision(Outcome::Hold, true))
+    );
+
+    // Only the first press can be held.
+    let (mut dance, _) = Dance::start(0, 0, 0, td, 0);
+    dance.release(td, 50);
+    dance.press(td, 100);
+    assert_eq!(
+        dance.tick(td, 200, 300),
+        Some(decision(Outcome::Taps(2), true))
+    );
+}
+
+#[test]
+fn dance_ends_when_more_taps_do_nothing() {
+    let td = &TAP_DANCES[0];
+    let (mut dance, _) = Dance::start(0, 0, 0, td, 0);
+    dance.release(td, 20);
+    dance.press(td, 40);
+    dance.release(td, 60);
+    assert_eq!(dance.press(td, 80), Some(decision(Outcome::Taps(3), true)));
+
+    let td = &TAP_DANCES[2];
+    let (mut dance, _) = Dance::start(0, 0, 2, td, 0);
+    assert_eq!(
+        dance.release(td, 20),
+        Some(decision(Outcome::Taps(1), false))
+    );
+}
+
+#[test]
+fn dance_interrupted() {
+    let td = &TAP_DANCES[0];
+    let (dance, _) = Dance::start(0, 0, 0, td, 0);
+    assert_eq!(dance.interrupt(), decision(Outcome::Taps(1), true));
+}
+
+#[test]
+fn single_tap() {
+    let mut kb = keyboard(&KEYMAP, |config| config.tap_dances = &TAP_DANCES);
+    tap(&mut kb, TD_ABC, 0);
+    assert!(reports(&mut kb).is_empty());
+    kb.tick(220);
+    assert_eq!(reports(&mut kb), [vec![A], vec![]]);
+}
+
+#[test]
+fn double_and_triple_tap() {
+    let mut kb = keyboard(&KEYMAP, |config| config.tap_dances = &TAP_DANCES);
+    tap(&mut kb, TD_ABC, 0);
+    tap(&mut kb, TD_ABC, 100);
+    kb.tick(320);
+    assert_eq!(reports(&mut kb), [vec![B], vec![]]);
+
+    tap(&mut kb, TD_ABC, 1000);
+    tap(&mut kb, TD_ABC, 1100);
+    press(&mut kb, TD_ABC, 1200);
+    // Decided right away, since there is nothing for a fourth tap.
+    assert_eq!(reports(&mut kb), [vec![C]]);
+    release(&mut kb, TD_ABC, 1300);
+    assert_eq!(reports(&mut kb), [vec![]]);
+}
+
+#[test]
+fn hold() {
+    let mut kb = keyboard(&KEYMAP, |config| config.tap_dances = &TAP_DANCES);
+    press(&mut kb, TD_ABC, 0);
+    kb.tick(199);
+    assert!(reports(&mut kb).is_empty());
+    kb.tick(200);
+    assert_eq!(reports(&mut kb), [vec![CTRL]]);
+    tap(&mut kb, KEY_X, 250);
+    release(&mut kb, TD_ABC, 300);
+    assert_eq!(reports(&mut kb), [vec![X, CTRL], vec![CTRL], vec![]]);
+}
+
+#[test]
+fn late_tap_starts_a_new_dance() {
+    let mut kb = keyboard(&KEYMAP, |config| config.tap_dances = &TAP_DANCES);
+    tap(&mut kb, TD_ESC, 0);
+    // No tick in between.
+    tap(&mut kb, TD_ESC, 500);
+    assert_eq!(reports(&mut kb), [vec![ESC], vec![]]);
+    tap(&mut kb, TD_ESC, 600);
+    assert_eq!(reports(&mut kb), [vec![CAPS], vec![]]);
+}
+
+#[test]
+fn held_without_hold_action() {
+    let mut kb = keyboard(&KEYMAP, |config| config.tap_dances = &TAP_DANCES);
+    press(&mut kb, TD_ESC, 0);
+    kb.tick(200);
+    assert_eq!(reports(&mut kb), [vec![ESC]]);
+    release(&mut kb, TD_ESC, 500);
+    assert_eq!(reports(&mut kb), [vec![]]);
+}
+
+#[test]
+fn other_key_interrupts() {
+    let mut kb = keyboard(&KEYMAP, |config| config.tap_dances = &TAP_DANCES);
+    tap(&mut kb, TD_ESC, 0);
+    tap(&mut kb, KEY_X, 50);
+    assert_eq!(reports(&mut kb), [vec![ESC], vec![], vec![X], vec![]]);
+
+    // Still held when interrupted.
+    press(&mut kb, TD_ESC, 1000);
+    press(&mut kb, KEY_X, 1050);
+    release(&mut kb, TD_ESC, 1100);
+    release(&mut kb, KEY_X, 1150);
+    assert_eq!(reports(&mut kb), [vec![ESC], vec![X, ESC], vec![X], vec![]]);
+}
+
+#[test]
+fn tapped_right_away_without_more_actions() {
+    let mut kb = keyboard(&KEYMAP, |config| config.tap_dances = &TAP_DANCES);
+    tap(&mut kb, TD_SPC, 0);
+    assert_eq!(reports(&mut kb), [vec![SPACE], vec![]]);
+}
+
+#[test]
+fn hold_layer() {
+    let mut kb = keyboard(&KEYMAP, |config| config.tap_dances = &TAP_DANCES);
+    press(&mut kb, TD_SPC, 0);
+    kb.tick(200);
+    tap(&mut kb, KEY_X, 250);
+    release(&mut kb, TD_SPC, 300);
+    tap(&mut kb, KEY_X, 350);
+    assert_eq!(reports(&mut kb), [vec![Y], vec![], vec![X], vec![]]);
+}
+
+#[test]
+fn undefined_tap_dance_does_nothing() {
+    static KEYMAP: [[[Keycode; 1]; 1]; 1] = [[[TD(3)]]];
+    let mut kb = Keyboard::new(
+        &KEYMAP,
+        Config {
+            tap_dances: &TAP_DANCES,
+            ..Config::new()
+        },
+    );
+    kb.event(KeyEvent::pressed(0, 0), 0);
+    kb.event(KeyEvent::released(0, 0), 20);
+    kb.tick(500);
+    assert!(kb.pop_report().is_none());
+}
+
+#[test]
+fn interrupting_key_sees_the_dance_layer() {
+    static TAP_DANCES: [TapDance; 1] = [TapDance::double(TG(1), KC_SPC)];
+    static KEYMAP: [[[Keycode; 2]; 1]; 2] = [[[TD(0), KC_X]], [[_______, KC_Y]]];
+    let mut kb = Keyboard::new(
+        &KEYMAP,
+        Config {
+            tap_dances: &TAP_DANCES,
+            ..Config::new()
+        },
+    );
+    kb.event(KeyEvent::pressed(0, 0), 0);
+    kb.event(KeyEvent::released(0, 0), 20);
+    kb.event(KeyEvent::pressed(0, 1), 50);
+    let report = kb.pop_report().unwrap();
+    assert!(report.is_pressed(Y));
+}
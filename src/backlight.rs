@@ -0,0 +1,43 @@
+//! Backlight PWM output on PB7 (OC1C), driven by TC1.
+
+use atmega_hal::{
+    pac::TC1,
+    port::{mode::Output, Pin, PB7},
+};
+
+// TCCR1A: fast PWM, 8-bit (WGM11:10 = 01), with OC1C cleared on compare match
+// (COM1C1:0 = 10) when the output is connected.
+const TCCR1A_DISCONNECTED: u8 = 0b0000_0001;
+const TCCR1A_CONNECTED: u8 = 0b0000_1001;
+// TCCR1B: fast PWM, 8-bit (WGM13:12 = 01), no prescaling (CS12:10 = 001).
+// 16MHz / 256 = 62.5kHz, well above anything visible.
+const TCCR1B: u8 = 0b0000_1001;
+
+pub struct BacklightPwm {
+    tc1: TC1,
+    _pin: Pin<Output, PB7>,
+}
+
+impl BacklightPwm {
+    pub fn new(tc1: TC1, pin: Pin<Output, PB7>) -> Self {
+        tc1.tccr1a.write(|w| unsafe { w.bits(TCCR1A_DISCONNECTED) });
+        tc1.tccr1b.write(|w| unsafe { w.bits(TCCR1B) });
+        Self { tc1, _pin: pin }
+    }
+
+    /// Sets the duty cycle, where 255 is fully on.
+    pub fn set_duty(&mut self, duty: u8) {
+        if duty == 0 {
+            // Fast PWM still outputs a one-cycle pulse at a compare value of
+            // 0, so disconnect the pin (left low) to turn the LEDs fully off.
+            self.tc1
+                .tccr1a
+                .write(|w| unsafe { w.bits(TCCR1A_DISCONNECTED) });
+        } else {
+            self.tc1.ocr1c.write(|w| w.bits(duty.into()));
+            self.tc1
+                .tccr1a
+                .write(|w| unsafe { w.bits(TCCR1A_CONNECTED) });
+        }
+    }
+}
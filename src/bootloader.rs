@@ -0,0 +1,51 @@
+//! Jumping to the DFU bootloader, so the keyboard can be flashed without
+//! pressing the reset button.
+
+use atmega_hal::{clock::MHz16, delay::Delay, pac::Peripherals, prelude::*};
+use avr_device::interrupt;
+
+/// Detaches from USB, returns the peripherals to their reset state and jumps
+/// to the bootloader.
+pub fn jump() -> ! {
+    interrupt::disable();
+
+    // Safety: interrupts are disabled and control never returns to the code
+    // that owns the peripherals.
+    let dp = unsafe { Peripherals::steal() };
+
+    // Detach and give the host time to notice, so it re-enumerates the
+    // bootloader instead of waiting for the keyboard.
+    dp.USB_DEVICE.udcon.reset();
+    dp.USB_DEVICE.usbcon.reset();
+    Delay::<MHz16>::new().delay_ms(5u8);
+
+    // The bootloader expects interrupt sources and pins as they are after a
+    // reset.
+    dp.TC0.timsk0.reset();
+    dp.TC1.timsk1.reset();
+    dp.TC3.timsk3.reset();
+    dp.TC4.timsk4.reset();
+    dp.EXINT.eimsk.reset();
+    dp.EXINT.pcicr.reset();
+    dp.USART1.ucsr1b.reset();
+    dp.ADC.adcsra.reset();
+    dp.PORTB.ddrb.reset();
+    dp.PORTB.portb.reset();
+    dp.PORTC.ddrc.reset();
+    dp.PORTC.portc.reset();
+    dp.PORTD.ddrd.reset();
+    dp.PORTD.portd.reset();
+    dp.PORTE.ddre.reset();
+    dp.PORTE.porte.reset();
+    dp.PORTF.ddrf.reset();
+    dp.PORTF.portf.reset();
+
+    #[cfg(target_arch = "avr")]
+    unsafe {
+        // The bootloader section starts at 0x7000 with the 4KB boot size
+        // the Planck's ATmega32U4 is fused for.
+        core::arch::asm!("jmp 0x7000", options(noreturn));
+    }
+    #[cfg(not(target_arch = "avr"))]
+    loop {}
+}
@@ -0,0 +1,78 @@
+//! The keymap.
+//!
+//! Building with `KEYMAP_JSON=path/to/keymap.json` replaces the default
+//! layers with the ones exported from QMK Configurator (see `build.rs`).
+
+pub use layers::{LAYERS, LAYER_CONDITIONS, TAP_DANCES};
+
+#[cfg(not(keymap_json))]
+mod layers {
+    use engine::{
+        keyboard::LayerCondition,
+        keycode::{qmk::*, Keycode},
+        keymap, layout_planck_grid,
+        tap_dance::TapDance,
+    };
+
+    use crate::scan;
+
+    const MO_LOWR: Keycode = MO(LOWER);
+    const MO_RAIS: Keycode = MO(RAISE);
+
+    keymap! {
+        pub static LAYERS: [[Keycode; scan::COLS]; scan::ROWS] = {
+            // Default/Base
+            BASE => layout_planck_grid![
+                KC_TAB , KC_Q   , KC_W   , KC_E   , KC_R   , KC_T   , KC_Y   , KC_U   , KC_I   , KC_O   , KC_P   , KC_BSPC,
+                KC_CLCK, KC_A   , KC_S   , KC_D   , KC_F   , KC_G   , KC_H   , KC_J   , KC_K   , KC_L   , KC_SCLN, KC_QUOT,
+                KC_LSFT, KC_Z   , KC_X   , KC_C   , KC_V   , KC_B   , KC_N   , KC_M   , KC_COMM, KC_DOT , KC_SLSH, KC_RSFT,
+                KC_LCTL, KC_LGUI, KC_LALT, XXXXXXX, MO_LOWR, KC_ENT , KC_SPC , MO_RAIS, XXXXXXX, KC_RALT, KC_RGUI, KC_RCTL,
+            ],
+            LOWER => layout_planck_grid![
+                KC_ESC , KC_F1  , KC_F2  , KC_F3  , KC_F4  , _______, KC_HOME, KC_PGDN, KC_PGUP, KC_END , KC_INS , _______,
+                _______, KC_F5  , KC_F6  , KC_F7  , KC_F8  , _______, KC_LEFT, KC_DOWN, KC_UP  , KC_RGHT, KC_DEL , _______,
+                _______, KC_F9  , KC_F10 , KC_F11 , KC_F12 , _______, _______, KC_PAUS, KC_PSCR, KC_SLCK, _______, _______,
+                _______, _______, _______, _______, _______, _______, _______, _______, _______, _______, _______, _______,
+            ],
+            RAISE => layout_planck_grid![
+                KC_GRV , KC_1   , KC_2   , KC_3   , KC_4   , KC_5   , KC_6   , KC_7   , KC_8   , KC_9   , KC_0   , _______,
+                _______, KC_ACL0, KC_BTN1, KC_MS_U, KC_BTN2, KC_WH_U, _______, KC_MINS, KC_EQL , KC_LBRC, KC_RBRC, KC_BSLS,
+                _______, KC_ACL1, KC_MS_L, KC_MS_D, KC_MS_R, KC_WH_D, _______, _______, _______, _______, _______, _______,
+                _______, _______, _______, _______, _______, _______, _______, _______, _______, _______, _______, _______,
+            ],
+            // Lower + Raise
+            ADJUST => layout_planck_grid![
+                _______, RESET  , _______, _______, _______, _______, _______, _______, _______, _______, _______, KC_DEL ,
+                _______, _______, _______, _______, _______, _______, _______, _______, _______, _______, _______, _______,
+                _______, BL_DEC , BL_INC , BL_STEP, BL_TOGG, BL_BRTG, _______, _______, _______, _______, _______, _______,
+                _______, _______, _______, _______, _______, _______, _______, _______, _______, _______, _______, _______,
+            ],
+        };
+    }
+
+    pub static LAYER_CONDITIONS: [LayerCondition; 1] =
+        [LayerCondition::tri_layer(LOWER, RAISE, ADJUST)];
+
+    // The actions of the `TD(index)` keys, e.g.
+    // `TapDance::double(KC_ESC, KC_CAPS)` for Escape, or Caps Lock when
+    // tapped twice.
+    pub static TAP_DANCES: [TapDance; 0] = [];
+}
+
+#[cfg(keymap_json)]
+#[allow(dead_code)]
+mod layers {
+    use engine::{
+        keyboard::LayerCondition,
+        keycode::{qmk::*, Keycode},
+        tap_dance::TapDance,
+    };
+
+    use crate::scan;
+
+    include!(concat!(env!("OUT_DIR"), "/keymap.rs"));
+
+    // Layer conditions and tap dances can't be expressed in keymap.json.
+    pub static LAYER_CONDITIONS: [LayerCondition; 0] = [];
+    pub static TAP_DANCES: [TapDance; 0] = [];
+}
@@ -2,8 +2,12 @@
 #![no_main]
 #![feature(abi_avr_interrupt, asm_experimental_arch)]
 
-pub mod keycode;
-mod nkro;
+mod backlight;
+mod bootloader;
+mod keymap;
+mod power;
+mod scan;
+mod timer;
 
 use core::mem::MaybeUninit;
 
@@ -16,72 +20,147 @@ use atmega_hal::{
     Peripherals,
 };
 use atmega_usbd::UsbBus;
-use avr_device::{asm::sleep, entry, interrupt};
+use avr_device::{entry, interrupt};
 use avr_std_stub as _;
-use keycode::{qmk::*, Keycode, LayerAction};
-use nkro::NkroKeyboardReport;
+use backlight::BacklightPwm;
+use engine::{
+    backlight::Backlight,
+    boot::BootKeyboardReport,
+    debounce::{Algorithm, Debouncer},
+    extrakey::{self, ExtraKeyReport},
+    host_leds::HostLeds,
+    keyboard::{Config, Keyboard},
+    keycode::SystemKeycode,
+    matrix::Matrix,
+    mousekey::MouseReport,
+    nkro::NkroKeyboardReport,
+    spsc::{Consumer, Producer, SpscQueue},
+};
 use usb_device::{
     class_prelude::UsbBusAllocator,
-    device::{UsbDevice, UsbDeviceBuilder, UsbVidPid},
+    device::{UsbDevice, UsbDeviceBuilder, UsbDeviceState, UsbVidPid},
+    UsbError,
+};
+use usbd_hid::{
+    descriptor::SerializedDescriptor,
+    hid_class::{
+        HIDClass, HidClassSettings, HidCountryCode, HidProtocol, HidProtocolMode, HidSubClass,
+        ProtocolModeConfig, ReportType,
+    },
 };
-use usbd_hid::{descriptor::SerializedDescriptor, hid_class::HIDClass};
-
-const LAYER_LOWER: u8 = 1;
-const LAYER_RAISE: u8 = 2;
-
-const MO_LOWR: Keycode = MO(LAYER_LOWER);
-const MO_RAIS: Keycode = MO(LAYER_RAISE);
-
-#[rustfmt::skip]
-static LAYERS: [[[Keycode; 12]; 4]; 3] = [
-    // 0: Default/Base
-    [
-        [KC_TAB , KC_Q   , KC_W   , KC_E   , KC_R   , KC_T   , KC_Y   , KC_U   , KC_I   , KC_O   , KC_P   , KC_BSPC],
-        [KC_CLCK, KC_A   , KC_S   , KC_D   , KC_F   , KC_G   , KC_H   , KC_J   , KC_K   , KC_L   , KC_SCLN, KC_QUOT],
-        [KC_LSFT, KC_Z   , KC_X   , KC_C   , KC_V   , KC_B   , KC_N   , KC_M   , KC_COMM, KC_DOT , KC_SLSH, KC_RSFT],
-        [KC_LCTL, KC_LGUI, KC_LALT, XXXXXXX, MO_LOWR, KC_ENT , KC_SPC , MO_RAIS, XXXXXXX, KC_RALT, KC_RGUI, KC_RCTL],
-    ],
-    // 1: Lower
-    [
-        [KC_ESC , KC_F1  , KC_F2  , KC_F3  , KC_F4  , _______, KC_HOME, KC_PGDN, KC_PGUP, KC_END , KC_INS , _______],
-        [_______, KC_F5  , KC_F6  , KC_F7  , KC_F8  , _______, KC_LEFT, KC_DOWN, KC_UP  , KC_RGHT, KC_DEL , _______],
-        [_______, KC_F9  , KC_F10 , KC_F11 , KC_F12 , _______, _______, KC_PAUS, KC_PSCR, KC_SLCK, _______, _______],
-        [_______, _______, _______, _______, _______, _______, _______, _______, _______, _______, _______, _______],
-    ],
-    // 2: Raise
-    [
-        [KC_GRV , KC_1   , KC_2   , KC_3   , KC_4   , KC_5   , KC_6   , KC_7   , KC_8   , KC_9   , KC_0   , _______],
-        [_______, _______, _______, _______, _______, _______, _______, KC_MINS, KC_EQL , KC_LBRC, KC_RBRC, KC_BSLS],
-        [_______, _______, _______, _______, _______, _______, _______, _______, _______, _______, _______, _______],
-        [_______, _______, _______, _______, _______, _______, _______, _______, _______, _______, _______, _______],
-    ],
-];
+
+const DEBOUNCE_ALGORITHM: Algorithm = Algorithm::DeferPerKey;
+/// Debounce window in milliseconds.
+const DEBOUNCE_WINDOW: u16 = 5;
+
+/// How long the host has to be suspended before a key press wakes it up, in
+/// milliseconds. The bus has to be idle for 5ms before a remote wakeup.
+const REMOTE_WAKEUP_DELAY: u16 = 10;
+
+/// Number of reports of each kind that can wait for the USB interrupts.
+/// When a queue is full, reports wait in the keyboard's own queues.
+const KEYBOARD_REPORT_QUEUE_LEN: usize = 8;
+const EXTRA_REPORT_QUEUE_LEN: usize = 4;
+const MOUSE_REPORT_QUEUE_LEN: usize = 4;
+
+const BACKLIGHT_LEVELS: u8 = 3;
+/// Duration of one backlight breath in milliseconds.
+const BACKLIGHT_BREATHING_PERIOD: u16 = 4096;
 
 struct UsbContext {
     device: UsbDevice<'static, UsbBus>,
     hid: HIDClass<'static, UsbBus>,
+    // System and consumer control keys.
+    extra: HIDClass<'static, UsbBus>,
+    mouse: HIDClass<'static, UsbBus>,
+    reports: Consumer<'static, NkroKeyboardReport, KEYBOARD_REPORT_QUEUE_LEN>,
+    extra_reports: Consumer<'static, ExtraKeyReport, EXTRA_REPORT_QUEUE_LEN>,
+    mouse_reports: Consumer<'static, MouseReport, MOUSE_REPORT_QUEUE_LEN>,
+    // The last keyboard report sent.
+    report: NkroKeyboardReport,
+    // Whether `report` has to be sent again.
+    resend: bool,
+    // Whether the host has switched to the boot protocol (SET_PROTOCOL).
+    boot: bool,
 }
 
 impl UsbContext {
     fn poll(&mut self, state: &mut UsbState) {
-        self.device.poll(&mut [&mut self.hid]);
-        if !state.sent && self.hid.push_input(&state.report).is_ok() {
-            state.sent = true;
+        self.device
+            .poll(&mut [&mut self.hid, &mut self.extra, &mut self.mouse]);
+        state.suspended = self.device.state() == UsbDeviceState::Suspend;
+        state.remote_wakeup_enabled = self.device.remote_wakeup_enabled();
+
+        // The host may send LED state on the OUT endpoint or as a SET_REPORT
+        // control request.
+        let mut buf = [0; 8];
+        if let Ok(len) = self.hid.pull_raw_output(&mut buf) {
+            if let Some(leds) = HostLeds::from_report(&buf[..len]) {
+                state.host_leds = leds;
+            }
+        }
+        if let Ok(info) = self.hid.pull_raw_report(&mut buf) {
+            if info.report_type == ReportType::Output {
+                if let Some(leds) = HostLeds::from_report(&buf[..info.len]) {
+                    state.host_leds = leds;
+                }
+            }
+        }
+
+        let boot = matches!(self.hid.get_protocol_mode(), Ok(HidProtocolMode::Boot));
+        if boot != self.boot {
+            // Resend the current state in the new format.
+            self.boot = boot;
+            self.resend = true;
+        }
+
+        // Each report is only taken off its queue once it has been sent, so
+        // the host sees every change, in order.
+        if self.resend {
+            if self.push_keyboard_report(self.report).is_ok() {
+                self.resend = false;
+            }
+        } else if let Some(report) = self.reports.peek() {
+            if self.push_keyboard_report(report).is_ok() {
+                self.reports.pop();
+                self.report = report;
+            }
+        }
+        if let Some(report) = self.extra_reports.peek() {
+            if self.extra.push_raw_input(&report.to_bytes()).is_ok() {
+                self.extra_reports.pop();
+            }
+        }
+        if let Some(report) = self.mouse_reports.peek() {
+            if self.mouse.push_input(&report).is_ok() {
+                self.mouse_reports.pop();
+            }
+        }
+    }
+
+    fn push_keyboard_report(&mut self, report: NkroKeyboardReport) -> Result<usize, UsbError> {
+        if self.boot {
+            self.hid
+                .push_raw_input(BootKeyboardReport::from_nkro(&report).as_bytes())
+        } else {
+            self.hid.push_input(&report)
         }
     }
 }
 
 #[derive(Clone, Copy)]
 struct UsbState {
-    report: NkroKeyboardReport,
-    sent: bool,
+    host_leds: HostLeds,
+    suspended: bool,
+    remote_wakeup_enabled: bool,
 }
 
 impl UsbState {
     const fn new() -> Self {
         Self {
-            report: NkroKeyboardReport::new(),
-            sent: true,
+            host_leds: HostLeds::from_bits(0),
+            suspended: false,
+            remote_wakeup_enabled: false,
         }
     }
 }
@@ -89,9 +168,18 @@ impl UsbState {
 // Resources sent to the USB interrupt contexts.
 static mut USB_CTX: MaybeUninit<UsbContext> = MaybeUninit::uninit();
 
-// State that is shared with USB interrupts (e.g. reports).
+// State that is shared with USB interrupts (e.g. host LEDs).
 static mut USB_STATE: UsbState = UsbState::new();
 
+// Key events from the timer interrupt to the main loop.
+static mut KEY_EVENTS: scan::KeyEventQueue = SpscQueue::new();
+
+// Reports from the main loop to the USB interrupts.
+static mut KEYBOARD_REPORTS: SpscQueue<NkroKeyboardReport, KEYBOARD_REPORT_QUEUE_LEN> =
+    SpscQueue::new();
+static mut EXTRA_REPORTS: SpscQueue<ExtraKeyReport, EXTRA_REPORT_QUEUE_LEN> = SpscQueue::new();
+static mut MOUSE_REPORTS: SpscQueue<MouseReport, MOUSE_REPORT_QUEUE_LEN> = SpscQueue::new();
+
 #[entry]
 fn main() -> ! {
     let dp = Peripherals::take().unwrap();
@@ -124,13 +212,13 @@ fn main() -> ! {
     pll.pllcsr.modify(|_, w| w.plle().set_bit());
     while pll.pllcsr.read().plock().bit_is_clear() {}
 
-    let mut rows: [Pin<Output>; 4] = [
+    let rows: [Pin<Output>; scan::ROWS] = [
         pins.pd0.into_output_high().downgrade(),
         pins.pd5.into_output_high().downgrade(),
         pins.pb5.into_output_high().downgrade(),
         pins.pb6.into_output_high().downgrade(),
     ];
-    let columns: [Pin<Input<PullUp>>; 12] = [
+    let columns: [Pin<Input<PullUp>>; scan::COLS] = [
         pins.pf1.into_pull_up_input().downgrade(),
         pins.pf0.into_pull_up_input().downgrade(),
         pins.pb0.into_pull_up_input().downgrade(),
@@ -144,103 +232,161 @@ fn main() -> ! {
         pins.pb4.into_pull_up_input().downgrade(),
         pins.pd7.into_pull_up_input().downgrade(),
     ];
-    let mut layer_mask = 1u8;
-    let mut pressed_keys = [0u16; 4];
+    // Safety: each queue is split once, before interrupts are enabled.
+    let (key_event_producer, mut key_events) = unsafe { KEY_EVENTS.split() };
+    let (mut reports, report_consumer) = unsafe { KEYBOARD_REPORTS.split() };
+    let (mut extra_reports, extra_report_consumer) = unsafe { EXTRA_REPORTS.split() };
+    let (mut mouse_reports, mouse_report_consumer) = unsafe { MOUSE_REPORTS.split() };
+
+    scan::start(scan::Scanner {
+        rows,
+        columns,
+        debouncer: Debouncer::new(DEBOUNCE_ALGORITHM, DEBOUNCE_WINDOW),
+        matrix: Matrix::new(),
+        events: key_event_producer,
+    });
+    let mut keyboard = Keyboard::new(
+        &keymap::LAYERS,
+        Config {
+            layer_conditions: &keymap::LAYER_CONDITIONS,
+            tap_dances: &keymap::TAP_DANCES,
+            ..Config::new()
+        },
+    );
+
+    let mut backlight = Backlight::new(BACKLIGHT_LEVELS, BACKLIGHT_BREATHING_PERIOD);
+    let mut backlight_pwm = BacklightPwm::new(dp.TC1, pins.pb7.into_output());
+
+    timer::init(dp.TC0);
 
     let bus = {
         static mut USB_BUS: MaybeUninit<UsbBusAllocator<UsbBus>> = MaybeUninit::uninit();
         unsafe { USB_BUS.write(UsbBus::new(dp.USB_DEVICE)) }
     };
 
-    let hid = HIDClass::new(bus, NkroKeyboardReport::desc(), 1);
+    // A boot keyboard interface, so the keyboard also works in BIOS/UEFI
+    // setup. Hosts that parse the report descriptor get NKRO reports.
+    let hid = HIDClass::new_with_settings(
+        bus,
+        NkroKeyboardReport::desc(),
+        1,
+        HidClassSettings {
+            subclass: HidSubClass::Boot,
+            protocol: HidProtocol::Keyboard,
+            config: ProtocolModeConfig::DefaultBehavior,
+            locale: HidCountryCode::NotSupported,
+        },
+    );
+    let extra = HIDClass::new(bus, extrakey::DESCRIPTOR, 10);
+    let mouse = HIDClass::new(bus, MouseReport::desc(), 10);
     let usb_device = UsbDeviceBuilder::new(bus, UsbVidPid(0x03a8, 0xae01))
         .manufacturer("OLKB")
         .product("Planck")
         .device_release(0x0002)
+        .supports_remote_wakeup(true)
         .build();
 
     unsafe {
         USB_CTX.write(UsbContext {
             device: usb_device,
             hid,
+            extra,
+            mouse,
+            reports: report_consumer,
+            extra_reports: extra_report_consumer,
+            mouse_reports: mouse_report_consumer,
+            report: NkroKeyboardReport::new(),
+            resend: false,
+            boot: false,
         });
     }
 
+    power::init(&dp.CPU);
+
+    // When the suspend was noticed, while suspended.
+    let mut suspended_since: Option<u16> = None;
+    // Whether a key was pressed while suspended and the host hasn't been
+    // woken up yet.
+    let mut wakeup_pending = false;
+
     unsafe { interrupt::enable() };
+    // Everything runs once per timer tick, independent of USB traffic.
     loop {
-        sleep();
-        let mut state = interrupt::free(|_cs| unsafe { USB_STATE });
-
-        if state.sent {
-            let mut changed = false;
-            for (i, row) in rows.iter_mut().enumerate() {
-                row.set_low();
-                for (j, col) in columns.iter().enumerate() {
-                    let prev_pressed = (pressed_keys[i] & (1 << j)) != 0;
-                    let pressed = col.is_low();
-
-                    if prev_pressed != pressed {
-                        let keycode = LAYERS
-                            .iter()
-                            .enumerate()
-                            .rev()
-                            .filter(|(k, _layer)| (layer_mask & (1 << k)) != 0)
-                            .map(|(_k, layer)| layer[i][j])
-                            .find(|kc| *kc != KC_TRNS)
-                            .unwrap_or(KC_NO);
-                        match keycode {
-                            Keycode::Hid(hid_keycode) => {
-                                if pressed {
-                                    state.report.press(hid_keycode as u8);
-                                } else {
-                                    state.report.release(hid_keycode as u8);
-                                }
-                            }
-                            Keycode::Layer(layer_keycode) => {
-                                match layer_keycode.action() {
-                                    LayerAction::Momentary => {
-                                        if pressed {
-                                            layer_mask |= 1 << layer_keycode.layer();
-                                        } else {
-                                            layer_mask &= !(1 << layer_keycode.layer());
-                                        }
-                                        state.report.clear_all_but_mods();
-                                    }
-                                    LayerAction::Toggle => {
-                                        if pressed {
-                                            layer_mask ^= 1 << layer_keycode.layer();
-                                            state.report.clear_all_but_mods();
-                                        }
-                                    }
-                                    LayerAction::Oneshot => {} //TODO
-                                    LayerAction::To => {}      //TODO
-                                }
-                            }
-                            _ => {}
-                        }
-                        changed = true;
-                    }
-
-                    if pressed {
-                        pressed_keys[i] |= 1 << j;
-                    } else {
-                        pressed_keys[i] &= !(1 << j);
-                    }
-                }
-                row.set_high();
+        let now = timer::wait_tick();
+        let state = interrupt::free(|_cs| unsafe { USB_STATE });
+        keyboard.set_host_leds(state.host_leds);
+
+        let mut pressed = false;
+        while let Some((event, time)) = key_events.pop() {
+            pressed |= event.action.is_pressed();
+            keyboard.event(event, time);
+        }
+        keyboard.tick(now);
+
+        if state.suspended {
+            let since = *suspended_since.get_or_insert(now);
+            // Keys pressed while suspended are sent once the host has resumed.
+            // A press during the delay wakes the host up once it has passed.
+            wakeup_pending |= pressed;
+            if wakeup_pending
+                && state.remote_wakeup_enabled
+                && now.wrapping_sub(since) >= REMOTE_WAKEUP_DELAY
+            {
+                power::remote_wakeup();
+                wakeup_pending = false;
+            }
+        } else {
+            suspended_since = None;
+            wakeup_pending = false;
+        }
+        // Checked again with interrupts disabled, so that a resume that has
+        // just come in isn't undone.
+        interrupt::free(|_cs| {
+            if unsafe { USB_STATE.suspended } {
+                power::suspend();
+            } else {
+                power::resume();
+            }
+        });
+
+        while let Some(system_keycode) = keyboard.pop_system_key() {
+            if system_keycode == SystemKeycode::Reset {
+                bootloader::jump();
             }
-            if changed {
-                state.sent = false;
-                interrupt::free(|_cs| unsafe {
-                    USB_STATE = state;
-                })
+            backlight.handle(system_keycode);
+        }
+        backlight_pwm.set_duty(if state.suspended {
+            0
+        } else {
+            backlight.duty(now)
+        });
+
+        forward(&mut reports, || keyboard.pop_report());
+        forward(&mut extra_reports, || keyboard.pop_extra_report());
+        forward(&mut mouse_reports, || keyboard.pop_mouse_report());
+    }
+}
+
+/// Moves reports to a USB interrupt's queue while it has room.
+fn forward<T: Copy, const N: usize>(
+    queue: &mut Producer<'static, T, N>,
+    mut pop: impl FnMut() -> Option<T>,
+) {
+    while !queue.is_full() {
+        match pop() {
+            Some(report) => {
+                let _ = queue.push(report);
             }
+            None => break,
         }
     }
 }
 
 #[interrupt(atmega32u4)]
 fn USB_GEN() {
+    // This may be the wakeup interrupt, which comes in with the USB clock
+    // frozen.
+    power::restart_clocks();
     let ctx = unsafe { USB_CTX.assume_init_mut() };
     let state = unsafe { &mut USB_STATE };
     ctx.poll(state);
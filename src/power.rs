@@ -0,0 +1,80 @@
+//! Saving power while the host is suspended, and waking the host back up.
+
+use atmega_hal::pac::{Peripherals, CPU};
+use avr_device::interrupt;
+
+use crate::timer;
+
+// Whether `suspend` has stopped the USB clock and slowed the timer down.
+static mut SUSPENDED: bool = false;
+// Whether a remote wakeup has been signalled and the host hasn't resumed yet.
+static mut WAKING: bool = false;
+
+/// Configures `sleep()` to idle the CPU until the next interrupt (the next
+/// timer tick at the latest), instead of doing nothing.
+pub fn init(cpu: &CPU) {
+    cpu.smcr.write(|w| w.sm().idle().se().set_bit());
+}
+
+/// Freezes the USB clock, turns the PLL off and slows the timer tick (and so
+/// the matrix scan) down to save power while the host is suspended. Does
+/// nothing if already done, or while waking the host up.
+///
+/// The USB controller still raises a wakeup interrupt on bus activity, which
+/// has to call `restart_clocks` before the USB driver touches the controller.
+pub fn suspend() {
+    interrupt::free(|_cs| {
+        if unsafe { SUSPENDED || WAKING } {
+            return;
+        }
+        // Safety: only registers that the USB driver doesn't touch while the
+        // device is suspended, with interrupts disabled.
+        let dp = unsafe { Peripherals::steal() };
+        dp.USB_DEVICE.usbcon.modify(|_, w| w.frzclk().set_bit());
+        dp.PLL.pllcsr.modify(|_, w| w.plle().clear_bit());
+        timer::set_slow(true);
+        unsafe { SUSPENDED = true };
+    });
+}
+
+/// Undoes `suspend` once the host has resumed.
+pub fn resume() {
+    interrupt::free(|_cs| {
+        unsafe { WAKING = false };
+        restart_clocks();
+    });
+}
+
+/// Undoes `suspend` for the USB driver, which doesn't work with the clock
+/// frozen. The main loop suspends again if the host is still suspended.
+pub fn restart_clocks() {
+    interrupt::free(|_cs| {
+        if !unsafe { SUSPENDED } {
+            return;
+        }
+        // Safety: as in `suspend`.
+        let dp = unsafe { Peripherals::steal() };
+        dp.PLL.pllcsr.modify(|_, w| w.plle().set_bit());
+        while dp.PLL.pllcsr.read().plock().bit_is_clear() {}
+        dp.USB_DEVICE.usbcon.modify(|_, w| w.frzclk().clear_bit());
+        timer::set_slow(false);
+        unsafe { SUSPENDED = false };
+    });
+}
+
+/// Signals a remote wakeup to the suspended host. Only allowed if the host
+/// has enabled it.
+pub fn remote_wakeup() {
+    interrupt::free(|_cs| {
+        // Safety: as in `suspend`.
+        let dp = unsafe { Peripherals::steal() };
+        if dp.USB_DEVICE.udcon.read().rmwkup().bit_is_set() {
+            // Still signalling.
+            return;
+        }
+        restart_clocks();
+        dp.USB_DEVICE.udcon.modify(|_, w| w.rmwkup().set_bit());
+        // Stay awake until the host resumes.
+        unsafe { WAKING = true };
+    });
+}
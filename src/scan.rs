@@ -0,0 +1,72 @@
+//! Matrix scanning, run from the timer interrupt on every tick so key events
+//! are timestamped on time however long the main loop takes.
+
+use atmega_hal::port::{
+    mode::{Input, Output, PullUp},
+    Pin,
+};
+use avr_device::interrupt;
+use engine::{
+    debounce::Debouncer,
+    matrix::{KeyEvent, Matrix},
+    spsc::{Producer, SpscQueue},
+};
+
+pub const ROWS: usize = 4;
+pub const COLS: usize = 12;
+
+/// Number of key events that can wait for the main loop. When the queue is
+/// full, further changes are picked up by a later scan.
+pub const KEY_EVENT_QUEUE_LEN: usize = 32;
+
+/// Key events with the time they happened at.
+pub type KeyEventQueue = SpscQueue<(KeyEvent, u16), KEY_EVENT_QUEUE_LEN>;
+
+pub struct Scanner {
+    pub rows: [Pin<Output>; ROWS],
+    pub columns: [Pin<Input<PullUp>>; COLS],
+    pub debouncer: Debouncer<ROWS, COLS>,
+    pub matrix: Matrix<ROWS>,
+    pub events: Producer<'static, (KeyEvent, u16), KEY_EVENT_QUEUE_LEN>,
+}
+
+// Only used by the timer interrupt once set.
+static mut SCANNER: Option<Scanner> = None;
+
+/// Starts scanning on every timer tick.
+pub fn start(scanner: Scanner) {
+    interrupt::free(|_cs| unsafe { SCANNER = Some(scanner) });
+}
+
+/// Scans the matrix, if started. Called by the timer interrupt.
+pub fn tick(now: u16) {
+    if let Some(scanner) = unsafe { SCANNER.as_mut() } {
+        scanner.scan(now);
+    }
+}
+
+impl Scanner {
+    fn scan(&mut self, now: u16) {
+        let scan = self.read();
+        let events = &mut self.events;
+        self.matrix
+            .try_update(self.debouncer.update(&scan, now), |event| {
+                events.push((event, now)).is_ok()
+            });
+    }
+
+    /// Reads the pressed keys, as a bitmask of columns for each row.
+    fn read(&mut self) -> [u16; ROWS] {
+        let mut scan = [0u16; ROWS];
+        for (row, bits) in self.rows.iter_mut().zip(&mut scan) {
+            row.set_low();
+            for (j, col) in self.columns.iter().enumerate() {
+                if col.is_low() {
+                    *bits |= 1 << j;
+                }
+            }
+            row.set_high();
+        }
+        scan
+    }
+}
@@ -0,0 +1,78 @@
+//! Millisecond timekeeping driven by TC0.
+
+use atmega_hal::pac::{Peripherals, TC0};
+use avr_device::{asm::sleep, interrupt};
+
+// Milliseconds since `init`. Incremented by the TC0 compare interrupt.
+static mut MILLIS: u32 = 0;
+// Set on every tick, cleared by `wait_tick`.
+static mut TICKED: bool = false;
+// Milliseconds per tick, changed by `set_slow`.
+static mut TICK_MS: u8 = 1;
+
+/// Configures TC0 to fire a compare interrupt once per millisecond.
+pub fn init(tc0: TC0) {
+    // 16MHz / 64 / 250 = 1kHz
+    tc0.tccr0a.write(|w| w.wgm0().ctc());
+    tc0.ocr0a.write(|w| w.bits(249));
+    tc0.tccr0b.write(|w| w.cs0().prescale_64());
+    tc0.timsk0.write(|w| w.ocie0a().set_bit());
+}
+
+/// Slows the tick down to once every 16ms, or back to once per millisecond.
+/// Time is counted in milliseconds either way.
+pub fn set_slow(slow: bool) {
+    interrupt::free(|_cs| {
+        // Safety: TC0 is only configured here and in `init`, with interrupts
+        // disabled.
+        let tc0 = unsafe { Peripherals::steal() }.TC0;
+        if slow {
+            // 16MHz / 1024 / 250 = 62.5Hz
+            tc0.tccr0b.write(|w| w.cs0().prescale_1024());
+            unsafe { TICK_MS = 16 };
+        } else {
+            tc0.tccr0b.write(|w| w.cs0().prescale_64());
+            unsafe { TICK_MS = 1 };
+        }
+    });
+}
+
+/// Milliseconds since `init`.
+///
+/// Monotonic for the first ~49 days, after which it wraps around.
+pub fn millis() -> u32 {
+    interrupt::free(|_cs| unsafe { MILLIS })
+}
+
+/// The current time in milliseconds, as used by the `engine` crate.
+///
+/// The counter wraps around every ~65 seconds, so durations should be
+/// computed with `wrapping_sub`.
+pub fn now() -> u16 {
+    millis() as u16
+}
+
+/// Sleeps until the next tick (or returns right away if one happened since
+/// the last call), returning the current time.
+///
+/// Other interrupts wake the CPU as well, but don't end the wait.
+pub fn wait_tick() -> u16 {
+    loop {
+        let ticked = interrupt::free(|_cs| unsafe { core::mem::replace(&mut TICKED, false) });
+        if ticked {
+            return now();
+        }
+        // A tick that comes in right before sleeping only wakes us up at the
+        // next interrupt, which is the next tick at the latest.
+        sleep();
+    }
+}
+
+#[interrupt(atmega32u4)]
+fn TIMER0_COMPA() {
+    unsafe {
+        MILLIS = MILLIS.wrapping_add(TICK_MS as u32);
+        TICKED = true;
+        crate::scan::tick(MILLIS as u16);
+    }
+}